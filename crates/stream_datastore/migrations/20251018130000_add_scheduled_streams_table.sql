@@ -0,0 +1,10 @@
+-- Add migration script here
+-- Upcoming and live streams, kept until they are archived and can be processed
+CREATE TABLE IF NOT EXISTS scheduled_streams (
+    video_id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    status TEXT NOT NULL,
+    scheduled_start TIMESTAMPTZ,
+    source TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,57 @@
+-- Add migration script here
+-- Sitting details are parsed from stream titles in Rust (see `SittingInfo`), which now writes `house` too
+
+-- 1. `house` keeps its values, but is no longer generated
+ALTER TABLE streams ALTER COLUMN house DROP EXPRESSION IF EXISTS;
+
+-- 2. Columns
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS sitting_date DATE;
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS sitting_session TEXT;
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS sitting_kind TEXT;
+
+-- 3. Backfill for existing rows, covering the usual "TUESDAY 10TH JUNE 2025" date format
+DO $$
+DECLARE
+  rec RECORD;
+BEGIN
+  FOR rec IN
+    SELECT video_id, regexp_match(
+      title,
+      '(\d{1,2})(?:ST|ND|RD|TH)?\s+(JANUARY|FEBRUARY|MARCH|APRIL|MAY|JUNE|JULY|AUGUST|SEPTEMBER|OCTOBER|NOVEMBER|DECEMBER),?\s+(\d{4})',
+      'i'
+    ) AS parts
+    FROM streams
+    WHERE sitting_date IS NULL
+  LOOP
+    CONTINUE WHEN rec.parts IS NULL;
+    BEGIN
+      UPDATE streams
+      SET sitting_date = to_date(rec.parts[1] || ' ' || rec.parts[2] || ' ' || rec.parts[3], 'DD MONTH YYYY')
+      WHERE video_id = rec.video_id;
+    EXCEPTION WHEN others THEN
+      -- impossible dates such as "31ST JUNE" are left out, as in Rust
+      NULL;
+    END;
+  END LOOP;
+END
+$$;
+
+UPDATE streams
+SET sitting_session = CASE
+    WHEN title ILIKE '%morning%' THEN 'morning'
+    WHEN title ILIKE '%afternoon%' THEN 'afternoon'
+    WHEN title ILIKE '%evening%' OR title ILIKE '%night%' THEN 'evening'
+  END
+WHERE sitting_session IS NULL;
+
+UPDATE streams
+SET sitting_kind = CASE
+    WHEN title ILIKE '%joint sitting%' THEN 'joint'
+    WHEN title ILIKE '%special sitting%' THEN 'special'
+    WHEN title ILIKE '%committee of the whole%' THEN 'plenary'
+    WHEN title ILIKE '%committee%' THEN 'committee'
+    WHEN title ILIKE '%national assembly%' OR title ILIKE '%senate%' THEN 'plenary'
+  END
+WHERE sitting_kind IS NULL;
+
+UPDATE streams SET house = 'all' WHERE title ILIKE '%joint sitting%';
@@ -0,0 +1,4 @@
+-- Add migration script here
+-- How precisely `stream_timestamp` is known, e.g. 'day' for "Streamed live on Jun 10, 2025" or
+-- 'month' for "Streamed 4 months ago". Left NULL for streams recorded before it was tracked.
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS stream_timestamp_precision TEXT;
@@ -0,0 +1,33 @@
+-- Add migration script here
+-- Categories are worked out in Rust (see `StreamCategory`) and written as e.g. 'national_assembly',
+-- 'joint_sitting', 'committee:<name>' or 'county_assembly:<county>'
+
+-- 1. Column
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS category TEXT;
+
+-- 2. Backfill for existing rows, mirroring `StreamCategory::from_title`: committees and county
+-- assemblies are named after the part of the title, between '|'s, that mentions them
+UPDATE streams
+SET category = CASE
+    WHEN title ILIKE '%county assembly%'
+      AND trim(regexp_replace(
+        (regexp_match(title, '([^|]*county assembly[^|]*)', 'i'))[1],
+        '\s*\mcounty assembly(\s+of)?\M\s*', ' ', 'i'
+      )) <> ''
+      THEN 'county_assembly:' || trim(regexp_replace(
+        (regexp_match(title, '([^|]*county assembly[^|]*)', 'i'))[1],
+        '\s*\mcounty assembly(\s+of)?\M\s*', ' ', 'i'
+      ))
+    WHEN title ILIKE '%joint sitting%' THEN 'joint_sitting'
+    WHEN title ILIKE '%special sitting%' THEN 'special_sitting'
+    WHEN title ILIKE '%committee%' AND title NOT ILIKE '%committee of the whole%'
+      THEN 'committee:' || trim((regexp_match(title, '([^|]*committee[^|]*)', 'i'))[1])
+    WHEN title ILIKE '%national assembly%' AND title ILIKE '%senate%' THEN 'other'
+    WHEN title ILIKE '%national assembly%' THEN 'national_assembly'
+    WHEN title ILIKE '%senate%' THEN 'senate'
+    ELSE 'other'
+  END
+WHERE category IS NULL;
+
+ALTER TABLE streams ALTER COLUMN category SET DEFAULT 'other';
+ALTER TABLE streams ALTER COLUMN category SET NOT NULL;
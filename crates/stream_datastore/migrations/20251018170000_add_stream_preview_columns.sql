@@ -0,0 +1,13 @@
+-- Add migration script here
+-- What YouTube lists about a stream besides its title, for previews of it
+
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS thumbnail_url TEXT;
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS description_snippet TEXT;
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS badges TEXT[] NOT NULL DEFAULT '{}';
+-- The duration as read out by screen readers, e.g. '3 hours, 2 minutes, 10 seconds'
+ALTER TABLE streams ADD COLUMN IF NOT EXISTS duration_label TEXT;
+
+-- Every video has a thumbnail at this URL, whatever its larger ones
+UPDATE streams
+SET thumbnail_url = 'https://i.ytimg.com/vi/' || video_id || '/hqdefault.jpg'
+WHERE thumbnail_url IS NULL;
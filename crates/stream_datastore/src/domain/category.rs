@@ -0,0 +1,238 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres, Type,
+};
+use std::{fmt::Display, str::FromStr, sync::LazyLock};
+
+use super::{House, SittingInfo, SittingKind};
+
+/// e.g. "NAIROBI CITY COUNTY ASSEMBLY", "County Assembly of Nakuru"
+static COUNTY_ASSEMBLY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s*\bcounty assembly(\s+of)?\b\s*").unwrap());
+
+/// What a stream covers, stored as the `category` column in the form of its [`Display`], e.g.
+/// `national_assembly` or `committee:DEPARTMENTAL COMMITTEE ON HEALTH`, which is also how it's
+/// written in stream sources.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum StreamCategory {
+    NationalAssembly,
+    Senate,
+    /// Both houses sitting together
+    JointSitting,
+    /// A sitting outside a house's calendar
+    SpecialSitting,
+    /// A committee hearing, named as in the stream's title
+    Committee {
+        name: String,
+    },
+    CountyAssembly {
+        county: String,
+    },
+    Other,
+}
+
+impl StreamCategory {
+    /// Categorises a stream by its title, e.g. "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025".
+    ///
+    /// Committees and county assemblies are named after the part of the title, between `|`s,
+    /// that mentions them.
+    pub fn from_title(title: &str) -> Self {
+        let sitting = SittingInfo::from_title(title);
+        let segment_with = |keyword: &str| {
+            title
+                .split('|')
+                .find(|segment| segment.to_uppercase().contains(keyword))
+                .map(str::trim)
+        };
+
+        if let Some(segment) = segment_with("COUNTY ASSEMBLY") {
+            let county = COUNTY_ASSEMBLY_REGEX
+                .replace(segment, " ")
+                .trim()
+                .to_string();
+            if !county.is_empty() {
+                return StreamCategory::CountyAssembly { county };
+            }
+        }
+
+        match (sitting.sitting_kind, sitting.house) {
+            (Some(SittingKind::Joint), _) => StreamCategory::JointSitting,
+            (Some(SittingKind::Special), _) => StreamCategory::SpecialSitting,
+            (Some(SittingKind::Committee), _) => match segment_with("COMMITTEE") {
+                Some(name) => StreamCategory::Committee {
+                    name: name.to_string(),
+                },
+                None => StreamCategory::Other,
+            },
+            (_, House::NationalAssembly) => StreamCategory::NationalAssembly,
+            (_, House::Senate) => StreamCategory::Senate,
+            (_, House::All | House::Unspecified) => StreamCategory::Other,
+        }
+    }
+}
+
+impl Display for StreamCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamCategory::NationalAssembly => write!(f, "national_assembly"),
+            StreamCategory::Senate => write!(f, "senate"),
+            StreamCategory::JointSitting => write!(f, "joint_sitting"),
+            StreamCategory::SpecialSitting => write!(f, "special_sitting"),
+            StreamCategory::Committee { name } => write!(f, "committee:{name}"),
+            StreamCategory::CountyAssembly { county } => write!(f, "county_assembly:{county}"),
+            StreamCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl FromStr for StreamCategory {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let category = match value.split_once(':') {
+            Some(("committee", name)) if !name.trim().is_empty() => StreamCategory::Committee {
+                name: name.trim().to_string(),
+            },
+            Some(("county_assembly", county)) if !county.trim().is_empty() => {
+                StreamCategory::CountyAssembly {
+                    county: county.trim().to_string(),
+                }
+            }
+            Some(_) => return Err(format!("Unknown stream category: {value}")),
+            None => match value {
+                "national_assembly" => StreamCategory::NationalAssembly,
+                "senate" => StreamCategory::Senate,
+                "joint_sitting" => StreamCategory::JointSitting,
+                "special_sitting" => StreamCategory::SpecialSitting,
+                "other" => StreamCategory::Other,
+                _ => return Err(format!("Unknown stream category: {value}")),
+            },
+        };
+
+        Ok(category)
+    }
+}
+
+impl TryFrom<String> for StreamCategory {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<StreamCategory> for String {
+    fn from(category: StreamCategory) -> Self {
+        category.to_string()
+    }
+}
+
+impl Type<Postgres> for StreamCategory {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for StreamCategory {
+    fn array_type_info() -> PgTypeInfo {
+        <String as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for StreamCategory {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <String as Encode<Postgres>>::encode(self.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for StreamCategory {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categorises_titles() {
+        let cases = [
+            (
+                "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025 | AFTERNOON SESSION",
+                StreamCategory::NationalAssembly,
+            ),
+            (
+                "SENATE | COMMITTEE OF THE WHOLE HOUSE | 19TH JUNE 2025",
+                StreamCategory::Senate,
+            ),
+            (
+                "JOINT SITTING OF PARLIAMENT | PRESIDENTIAL ADDRESS",
+                StreamCategory::JointSitting,
+            ),
+            (
+                "SENATE | SPECIAL SITTING | THURSDAY 19TH JUNE 2025",
+                StreamCategory::SpecialSitting,
+            ),
+            (
+                "DEPARTMENTAL COMMITTEE ON FINANCE AND NATIONAL PLANNING | FINANCE BILL 2025",
+                StreamCategory::Committee {
+                    name: "DEPARTMENTAL COMMITTEE ON FINANCE AND NATIONAL PLANNING".to_string(),
+                },
+            ),
+            (
+                "NAIROBI CITY COUNTY ASSEMBLY | AFTERNOON SITTING",
+                StreamCategory::CountyAssembly {
+                    county: "NAIROBI CITY".to_string(),
+                },
+            ),
+            (
+                "County Assembly of Nakuru | Budget Committee",
+                StreamCategory::CountyAssembly {
+                    county: "Nakuru".to_string(),
+                },
+            ),
+            ("Bunge Bits Weekly", StreamCategory::Other),
+        ];
+
+        for (title, expected) in cases {
+            assert_eq!(StreamCategory::from_title(title), expected, "{title}");
+        }
+    }
+
+    #[test]
+    fn round_trips_through_strings() {
+        for category in [
+            StreamCategory::NationalAssembly,
+            StreamCategory::Senate,
+            StreamCategory::JointSitting,
+            StreamCategory::SpecialSitting,
+            StreamCategory::Committee {
+                name: "Public Accounts Committee".to_string(),
+            },
+            StreamCategory::CountyAssembly {
+                county: "Mombasa".to_string(),
+            },
+            StreamCategory::Other,
+        ] {
+            assert_eq!(category.to_string().parse(), Ok(category.clone()));
+            let json = serde_json::to_string(&category).unwrap();
+            assert_eq!(
+                serde_json::from_str::<StreamCategory>(&json).unwrap(),
+                category
+            );
+        }
+
+        assert!("committee:".parse::<StreamCategory>().is_err());
+        assert!("house".parse::<StreamCategory>().is_err());
+    }
+}
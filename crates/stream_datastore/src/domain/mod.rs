@@ -1,3 +1,11 @@
+mod category;
+mod published_time;
+mod scheduled_stream;
+mod sitting;
 mod stream;
 
-pub use stream::{Stream, StreamCategory, TIME_AGO_REGEX};
+pub use category::StreamCategory;
+pub use published_time::{PublishedTime, TimePrecision};
+pub use scheduled_stream::{LiveStatus, ScheduledStream};
+pub use sitting::{House, Session, SittingInfo, SittingKind};
+pub use stream::Stream;
@@ -0,0 +1,391 @@
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
+use std::fmt::Display;
+
+/// Words marking a relative time, e.g. "2 days ago", "il y a 2 jours", "hace 2 días",
+/// "há 2 dias", "vor 2 Tagen" or "siku 2 zilizopita"
+const RELATIVE_MARKERS: [&str; 8] = [
+    "ago",
+    "hace",
+    "há",
+    "vor",
+    "zilizopita",
+    "iliyopita",
+    "uliopita",
+    "lililopita",
+];
+
+/// Units of relative times, singular and plural, in the locales YouTube is read in
+const UNITS: [(TimePrecision, &[&str]); 7] = [
+    (
+        TimePrecision::Second,
+        &[
+            "second", "seconds", "seconde", "secondes", "segundo", "segundos", "sekunde",
+            "sekunden",
+        ],
+    ),
+    (
+        TimePrecision::Minute,
+        &[
+            "minute", "minutes", "minuto", "minutos", "minuten", "dakika",
+        ],
+    ),
+    (
+        TimePrecision::Hour,
+        &[
+            "hour", "hours", "heure", "heures", "hora", "horas", "stunde", "stunden", "saa",
+        ],
+    ),
+    (
+        TimePrecision::Day,
+        &[
+            "day", "days", "jour", "jours", "día", "días", "dia", "dias", "tag", "tagen", "siku",
+        ],
+    ),
+    (
+        TimePrecision::Week,
+        &[
+            "week", "weeks", "semaine", "semaines", "semana", "semanas", "woche", "wochen", "wiki",
+        ],
+    ),
+    (
+        TimePrecision::Month,
+        &[
+            "month", "months", "mois", "mes", "meses", "monat", "monaten", "mwezi", "miezi",
+        ],
+    ),
+    (
+        TimePrecision::Year,
+        &[
+            "year", "years", "an", "ans", "año", "años", "ano", "anos", "jahr", "jahren", "mwaka",
+            "miaka",
+        ],
+    ),
+];
+
+/// Month names in the same locales, matched in full or by their first three letters or more,
+/// e.g. "Jun", "juin", "junio", "Juni"
+const MONTHS: [&[&str]; 12] = [
+    &["january", "janvier", "enero", "januar", "januari"],
+    &["february", "février", "febrero", "februar", "februari"],
+    &["march", "mars", "marzo", "märz", "machi"],
+    &["april", "avril", "abril", "aprili"],
+    &["may", "mai", "mayo", "mei"],
+    &["june", "juin", "junio", "juni"],
+    &["july", "juillet", "julio", "juli", "julai"],
+    &["august", "août", "agosto", "agosti"],
+    &["september", "septembre", "septiembre", "septemba"],
+    &["october", "octobre", "octubre", "oktober", "oktoba"],
+    &["november", "novembre", "noviembre", "novemba"],
+    &["december", "décembre", "diciembre", "dezember", "desemba"],
+];
+
+/// When a stream was published, as read from YouTube's "published time" text, e.g.
+/// "Streamed 2 days ago" or "Streamed live on Jun 10, 2025".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublishedTime {
+    pub timestamp: DateTime<Utc>,
+    pub precision: TimePrecision,
+}
+
+/// How closely a [`PublishedTime`] is known: to the unit of a relative time, or to the day
+/// for an absolute date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimePrecision {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl TimePrecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimePrecision::Second => "second",
+            TimePrecision::Minute => "minute",
+            TimePrecision::Hour => "hour",
+            TimePrecision::Day => "day",
+            TimePrecision::Week => "week",
+            TimePrecision::Month => "month",
+            TimePrecision::Year => "year",
+        }
+    }
+}
+
+impl Display for TimePrecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl PublishedTime {
+    /// Parses a published time relative to `now`, whatever words surround it.
+    ///
+    /// Relative times are counted back from `now` by calendar months and years. Absolute
+    /// dates are taken at midnight UTC, numeric ones being read day first unless they start
+    /// with the year.
+    pub fn parse(text: &str, now: DateTime<Utc>) -> Option<Self> {
+        let text = text.to_lowercase();
+        let words = text
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '•'))
+            .map(|word| word.trim_end_matches('.'))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+
+        let is_relative =
+            text.contains("il y a") || words.iter().any(|word| RELATIVE_MARKERS.contains(word));
+        if is_relative {
+            return parse_relative(&words, now);
+        }
+
+        let date = words
+            .iter()
+            .find_map(|word| parse_numeric_date(word))
+            .or_else(|| parse_written_date(&words))?;
+
+        Some(PublishedTime {
+            timestamp: date.and_hms_opt(0, 0, 0)?.and_utc(),
+            precision: TimePrecision::Day,
+        })
+    }
+}
+
+/// e.g. `["streamed", "2", "days", "ago"]`, or `["siku", "2", "zilizopita"]` with the unit first
+fn parse_relative(words: &[&str], now: DateTime<Utc>) -> Option<PublishedTime> {
+    let (amount, precision) = words.iter().enumerate().find_map(|(i, word)| {
+        let amount = word.parse::<u32>().ok()?;
+        let unit = words
+            .get(i + 1)
+            .and_then(|next| unit(next))
+            .or_else(|| i.checked_sub(1).and_then(|i| unit(words[i])))?;
+        Some((amount, unit))
+    })?;
+
+    let timestamp = match precision {
+        TimePrecision::Second => now - Duration::seconds(amount.into()),
+        TimePrecision::Minute => now - Duration::minutes(amount.into()),
+        TimePrecision::Hour => now - Duration::hours(amount.into()),
+        TimePrecision::Day => now - Duration::days(amount.into()),
+        TimePrecision::Week => now - Duration::weeks(amount.into()),
+        TimePrecision::Month => now.checked_sub_months(Months::new(amount))?,
+        TimePrecision::Year => now.checked_sub_months(Months::new(amount.checked_mul(12)?))?,
+    };
+
+    Some(PublishedTime {
+        timestamp,
+        precision,
+    })
+}
+
+fn unit(word: &str) -> Option<TimePrecision> {
+    UNITS
+        .iter()
+        .find(|(_, names)| names.contains(&word))
+        .map(|(precision, _)| *precision)
+}
+
+/// e.g. "10/06/2025", "10.06.2025" or "2025-06-10"
+fn parse_numeric_date(word: &str) -> Option<NaiveDate> {
+    let parts = word
+        .split(['/', '.', '-'])
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    match parts[..] {
+        [year, month, day] if year > 31 => NaiveDate::from_ymd_opt(year as i32, month, day),
+        [day, month, year] if year > 31 => NaiveDate::from_ymd_opt(year as i32, month, day),
+        _ => None,
+    }
+}
+
+/// e.g. `["jun", "10", "2025"]`, `["10", "de", "junio", "de", "2025"]`
+fn parse_written_date(words: &[&str]) -> Option<NaiveDate> {
+    let month = words.iter().find_map(|word| month_number(word))?;
+    let numbers = words
+        .iter()
+        .filter_map(|word| word.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    let year = numbers.iter().find(|number| **number > 31)?;
+    let day = numbers.iter().find(|number| (1..=31).contains(*number))?;
+
+    NaiveDate::from_ymd_opt(*year as i32, month, *day)
+}
+
+fn month_number(word: &str) -> Option<u32> {
+    if word.chars().count() < 3 {
+        return None;
+    }
+
+    MONTHS
+        .iter()
+        .position(|names| names.iter().any(|name| name.starts_with(word)))
+        .map(|i| i as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Published times as YouTube writes them, and what they're expected to parse to
+    /// relative to 2025-06-24T12:00:00Z
+    const CASES: &[(&str, Option<(&str, TimePrecision)>)] = &[
+        // English, relative
+        (
+            "1 second ago",
+            Some(("2025-06-24T11:59:59Z", TimePrecision::Second)),
+        ),
+        (
+            "45 minutes ago",
+            Some(("2025-06-24T11:15:00Z", TimePrecision::Minute)),
+        ),
+        (
+            "1 hour ago",
+            Some(("2025-06-24T11:00:00Z", TimePrecision::Hour)),
+        ),
+        (
+            "Streamed 2 days ago",
+            Some(("2025-06-22T12:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "Streamed 1 day ago",
+            Some(("2025-06-23T12:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "Premiered 3 weeks ago",
+            Some(("2025-06-03T12:00:00Z", TimePrecision::Week)),
+        ),
+        (
+            "Streamed 4 months ago",
+            Some(("2025-02-24T12:00:00Z", TimePrecision::Month)),
+        ),
+        (
+            "1 month ago",
+            Some(("2025-05-24T12:00:00Z", TimePrecision::Month)),
+        ),
+        (
+            "Streamed 2 years ago",
+            Some(("2023-06-24T12:00:00Z", TimePrecision::Year)),
+        ),
+        // English, absolute
+        (
+            "Streamed live on Jun 10, 2025",
+            Some(("2025-06-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "Premiered Sep 3, 2024",
+            Some(("2024-09-03T00:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "10 June 2025",
+            Some(("2025-06-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "Streamed live on 2025-06-10",
+            Some(("2025-06-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        // French
+        (
+            "il y a 3 jours",
+            Some(("2025-06-21T12:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "Diffusé il y a 1 mois",
+            Some(("2025-05-24T12:00:00Z", TimePrecision::Month)),
+        ),
+        (
+            "Diffusé en direct le 10 juin 2025",
+            Some(("2025-06-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "Diffusé le 5 févr. 2025",
+            Some(("2025-02-05T00:00:00Z", TimePrecision::Day)),
+        ),
+        // Spanish
+        (
+            "Emitido hace 2 semanas",
+            Some(("2025-06-10T12:00:00Z", TimePrecision::Week)),
+        ),
+        (
+            "hace 1 año",
+            Some(("2024-06-24T12:00:00Z", TimePrecision::Year)),
+        ),
+        (
+            "Transmitido el 10 jun 2025",
+            Some(("2025-06-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "10 de agosto de 2024",
+            Some(("2024-08-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        // Portuguese
+        (
+            "Transmitido há 5 horas",
+            Some(("2025-06-24T07:00:00Z", TimePrecision::Hour)),
+        ),
+        // German
+        (
+            "Vor 2 Tagen live übertragen",
+            Some(("2025-06-22T12:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "vor 1 Monat",
+            Some(("2025-05-24T12:00:00Z", TimePrecision::Month)),
+        ),
+        (
+            "Live übertragen am 10.06.2025",
+            Some(("2025-06-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "Premiere am 3. Okt. 2024",
+            Some(("2024-10-03T00:00:00Z", TimePrecision::Day)),
+        ),
+        // Swahili, unit before the number
+        (
+            "Ilitiririshwa siku 2 zilizopita",
+            Some(("2025-06-22T12:00:00Z", TimePrecision::Day)),
+        ),
+        (
+            "saa 3 zilizopita",
+            Some(("2025-06-24T09:00:00Z", TimePrecision::Hour)),
+        ),
+        (
+            "miaka 2 iliyopita",
+            Some(("2023-06-24T12:00:00Z", TimePrecision::Year)),
+        ),
+        (
+            "Ilitiririshwa tarehe 10 Juni 2025",
+            Some(("2025-06-10T00:00:00Z", TimePrecision::Day)),
+        ),
+        // Not published times
+        ("", None),
+        ("invalid date format", None),
+        ("Streamed live on Jun 31, 2025", None),
+        ("2 days", None),
+        ("Scheduled for", None),
+    ];
+
+    #[test]
+    fn parses_published_times() {
+        let now = "2025-06-24T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        for (text, expected) in CASES {
+            let expected = expected.map(|(timestamp, precision)| PublishedTime {
+                timestamp: timestamp.parse().unwrap(),
+                precision,
+            });
+            assert_eq!(PublishedTime::parse(text, now), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn counts_back_calendar_months() {
+        let now = "2025-03-31T08:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert_eq!(
+            PublishedTime::parse("1 month ago", now).map(|time| time.timestamp),
+            "2025-02-28T08:00:00Z".parse().ok()
+        );
+    }
+}
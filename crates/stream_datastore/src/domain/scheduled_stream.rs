@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::fmt::Display;
+
+/// A stream that is announced or still being broadcast, so it can be processed as soon as
+/// it has been archived.
+#[derive(Debug, FromRow, Clone, PartialEq, Eq)]
+pub struct ScheduledStream {
+    pub video_id: String,
+    pub title: String,
+    #[sqlx(try_from = "String")]
+    pub status: LiveStatus,
+    /// When the stream is set to start, only announced for upcoming streams
+    pub scheduled_start: Option<DateTime<Utc>>,
+    /// Name of the configured source (channel or playlist) the stream was discovered through
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveStatus {
+    Upcoming,
+    Live,
+}
+
+impl LiveStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LiveStatus::Upcoming => "upcoming",
+            LiveStatus::Live => "live",
+        }
+    }
+}
+
+impl TryFrom<String> for LiveStatus {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "upcoming" => Ok(LiveStatus::Upcoming),
+            "live" => Ok(LiveStatus::Live),
+            _ => Err(format!("Unknown live status: {value}")),
+        }
+    }
+}
+
+impl Display for LiveStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
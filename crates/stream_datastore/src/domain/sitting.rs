@@ -0,0 +1,264 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+const MONTHS: &str = "JANUARY|FEBRUARY|MARCH|APRIL|MAY|JUNE|JULY|AUGUST|SEPTEMBER|OCTOBER|NOVEMBER|DECEMBER|JAN|FEB|MAR|APR|JUN|JUL|AUG|SEPT|SEP|OCT|NOV|DEC";
+
+/// e.g. "10TH JUNE 2025", "1ST JULY, 2025"
+static DAY_MONTH_YEAR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b(\d{{1,2}})(?:ST|ND|RD|TH)?\s+({MONTHS})\.?,?\s+(\d{{4}})\b"
+    ))
+    .unwrap()
+});
+/// e.g. "JUNE 10TH, 2025"
+static MONTH_DAY_YEAR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b({MONTHS})\.?\s+(\d{{1,2}})(?:ST|ND|RD|TH)?,?\s+(\d{{4}})\b"
+    ))
+    .unwrap()
+});
+/// e.g. "10/06/2025", "10.06.2025", day first
+static NUMERIC_DATE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(\d{1,2})[./-](\d{1,2})[./-](\d{4})\b").unwrap());
+
+/// What a stream's title says about the sitting it covers, e.g.
+/// "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025 | AFTERNOON SESSION".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SittingInfo {
+    pub house: House,
+    pub date: Option<NaiveDate>,
+    pub session: Option<Session>,
+    pub sitting_kind: Option<SittingKind>,
+}
+
+impl SittingInfo {
+    /// Parses a stream title, leaving out whatever it doesn't mention.
+    pub fn from_title(title: &str) -> Self {
+        let upper = title.to_uppercase();
+        let names_assembly = upper.contains("NATIONAL ASSEMBLY");
+        let names_senate = upper.contains("SENATE");
+
+        let sitting_kind = if upper.contains("JOINT SITTING") {
+            Some(SittingKind::Joint)
+        } else if upper.contains("SPECIAL SITTING") {
+            Some(SittingKind::Special)
+        } else if upper.contains("COMMITTEE OF THE WHOLE") {
+            // the whole House sitting as a committee, in the chamber
+            Some(SittingKind::Plenary)
+        } else if upper.contains("COMMITTEE") {
+            Some(SittingKind::Committee)
+        } else if names_assembly || names_senate {
+            Some(SittingKind::Plenary)
+        } else {
+            None
+        };
+
+        let house = match (names_assembly, names_senate) {
+            (true, true) => House::All,
+            _ if sitting_kind == Some(SittingKind::Joint) => House::All,
+            (true, false) => House::NationalAssembly,
+            (false, true) => House::Senate,
+            (false, false) => House::Unspecified,
+        };
+
+        let session = if upper.contains("MORNING") {
+            Some(Session::Morning)
+        } else if upper.contains("AFTERNOON") {
+            Some(Session::Afternoon)
+        } else if upper.contains("EVENING") || upper.contains("NIGHT") {
+            Some(Session::Evening)
+        } else {
+            None
+        };
+
+        SittingInfo {
+            house,
+            date: parse_title_date(title),
+            session,
+            sitting_kind,
+        }
+    }
+}
+
+/// Finds the first date written out in a title
+fn parse_title_date(title: &str) -> Option<NaiveDate> {
+    if let Some(captures) = DAY_MONTH_YEAR_REGEX.captures(title) {
+        return NaiveDate::from_ymd_opt(
+            captures[3].parse().ok()?,
+            month_number(&captures[2])?,
+            captures[1].parse().ok()?,
+        );
+    }
+    if let Some(captures) = MONTH_DAY_YEAR_REGEX.captures(title) {
+        return NaiveDate::from_ymd_opt(
+            captures[3].parse().ok()?,
+            month_number(&captures[1])?,
+            captures[2].parse().ok()?,
+        );
+    }
+    let captures = NUMERIC_DATE_REGEX.captures(title)?;
+    NaiveDate::from_ymd_opt(
+        captures[3].parse().ok()?,
+        captures[2].parse().ok()?,
+        captures[1].parse().ok()?,
+    )
+}
+
+fn month_number(month: &str) -> Option<u32> {
+    let month = month.to_uppercase();
+    MONTHS
+        .split('|')
+        .take(12)
+        .position(|name| name.starts_with(&month[..3.min(month.len())]))
+        .map(|i| i as u32 + 1)
+}
+
+/// The house a sitting is of, stored as the `house` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum House {
+    NationalAssembly,
+    Senate,
+    /// Both houses, e.g. in a joint sitting
+    All,
+    Unspecified,
+}
+
+impl House {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            House::NationalAssembly => "national assembly",
+            House::Senate => "senate",
+            House::All => "all",
+            House::Unspecified => "unspecified",
+        }
+    }
+}
+
+impl Display for House {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Session {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+impl Session {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Session::Morning => "morning",
+            Session::Afternoon => "afternoon",
+            Session::Evening => "evening",
+        }
+    }
+}
+
+impl Display for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SittingKind {
+    /// A regular sitting of a house, in its chamber
+    Plenary,
+    Committee,
+    /// A sitting outside the house's calendar, called by the Speaker
+    Special,
+    /// Both houses sitting together, e.g. for a presidential address
+    Joint,
+}
+
+impl SittingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SittingKind::Plenary => "plenary",
+            SittingKind::Committee => "committee",
+            SittingKind::Special => "special",
+            SittingKind::Joint => "joint",
+        }
+    }
+}
+
+impl Display for SittingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    #[test]
+    fn parses_plenary_titles() {
+        assert_eq!(
+            SittingInfo::from_title(
+                "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025 | AFTERNOON SESSION"
+            ),
+            SittingInfo {
+                house: House::NationalAssembly,
+                date: date(2025, 6, 10),
+                session: Some(Session::Afternoon),
+                sitting_kind: Some(SittingKind::Plenary),
+            }
+        );
+        assert_eq!(
+            SittingInfo::from_title(
+                "Senate Plenary, Wednesday 1st October, 2025 - Morning Sitting"
+            ),
+            SittingInfo {
+                house: House::Senate,
+                date: date(2025, 10, 1),
+                session: Some(Session::Morning),
+                sitting_kind: Some(SittingKind::Plenary),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_committee_special_and_joint_sittings() {
+        let committee = SittingInfo::from_title(
+            "DEPARTMENTAL COMMITTEE ON FINANCE AND NATIONAL PLANNING | FINANCE BILL 2025 | 06/05/2025",
+        );
+        assert_eq!(committee.house, House::Unspecified);
+        assert_eq!(committee.sitting_kind, Some(SittingKind::Committee));
+        assert_eq!(committee.date, date(2025, 5, 6));
+        assert_eq!(committee.session, None);
+
+        let whole_house = SittingInfo::from_title(
+            "NATIONAL ASSEMBLY | COMMITTEE OF THE WHOLE HOUSE | JUNE 19TH, 2025",
+        );
+        assert_eq!(whole_house.sitting_kind, Some(SittingKind::Plenary));
+        assert_eq!(whole_house.date, date(2025, 6, 19));
+
+        let special = SittingInfo::from_title("SENATE | SPECIAL SITTING | THURSDAY 19TH JUNE 2025");
+        assert_eq!(special.house, House::Senate);
+        assert_eq!(special.sitting_kind, Some(SittingKind::Special));
+
+        let joint = SittingInfo::from_title("JOINT SITTING OF PARLIAMENT | PRESIDENTIAL ADDRESS");
+        assert_eq!(joint.house, House::All);
+        assert_eq!(joint.sitting_kind, Some(SittingKind::Joint));
+        assert_eq!(joint.date, None);
+    }
+
+    #[test]
+    fn ignores_impossible_dates() {
+        assert_eq!(
+            SittingInfo::from_title("NATIONAL ASSEMBLY | 31ST JUNE 2025").date,
+            None
+        );
+        assert_eq!(SittingInfo::from_title("Bunge Bits").sitting_kind, None);
+    }
+}
@@ -1,24 +1,32 @@
-use chrono::{DateTime, Duration, Utc};
-use regex::Regex;
+use chrono::{DateTime, Utc};
 use sqlx::FromRow;
-use std::fmt::Display;
-use std::sync::LazyLock;
 
-pub static TIME_AGO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(\d+)\s+(second|minute|hour|day|week|month|year)s?\s+ago").unwrap()
-});
+use super::{PublishedTime, SittingInfo, StreamCategory};
 
 #[derive(Debug, FromRow, Clone, Default)]
 pub struct Stream {
     pub video_id: String,
     pub title: String,
     pub view_count: String,
-    /// Initially fetched stream date from youtube in "time ago" format. This is easily expired when persisted, hence
-    /// the need to infer a timestamp using the `timestamp_from_time_ago` function
+    /// Initially fetched stream date from youtube, e.g. "Streamed 2 days ago" or "Streamed live on Jun 10, 2025".
+    /// Relative dates easily expire when persisted, hence the need to infer a timestamp using the `published_time` function
     pub streamed_date: String,
     pub duration: String,
     pub summary_md: Option<String>,
     pub timestamp_md: Option<String>,
+    /// Name of the configured source (channel or playlist) the stream was discovered through
+    pub source: Option<String>,
+    /// URL of the largest thumbnail YouTube lists for the stream
+    pub thumbnail_url: Option<String>,
+    /// Start of the stream's description, as shown in listings
+    pub description_snippet: Option<String>,
+    /// Labels YouTube shows on the stream, e.g. "CC" when it has captions
+    pub badges: Vec<String>,
+    /// The duration as read out by screen readers, e.g. "3 hours, 2 minutes, 10 seconds"
+    pub duration_label: Option<String>,
+    /// The category the stream was discovered under, e.g. its source's default, or `None` to
+    /// categorise it by its title
+    pub category: Option<StreamCategory>,
 }
 
 impl Stream {
@@ -31,73 +39,41 @@ impl Stream {
         format!("https://www.youtube.com/watch?v={}", self.video_id)
     }
 
-    /// Attempts to parse the `streamed_date` field and convert it to a `DateTime<Utc>`.
-    ///
-    /// This method interprets strings in the format "X units ago" where units can be
-    /// seconds, minutes, hours, days, weeks, months, or years. It then calculates
-    /// an approximate timestamp based on the current time.
+    /// Parses the `streamed_date` field, relative (e.g. "Streamed 2 days ago") or absolute
+    /// (e.g. "Streamed live on Jun 10, 2025"), in any of the locales [`PublishedTime`] reads.
     ///
     /// # Returns
     ///
-    /// - `Some(DateTime<Utc>)` if the `streamed_date` was successfully parsed.
-    /// - `None` if the `streamed_date` couldn't be parsed or doesn't match the expected format.
+    /// - `Some(PublishedTime)` with the timestamp and how precisely it's known.
+    /// - `None` if the `streamed_date` couldn't be parsed.
+    pub fn published_time(&self) -> Option<PublishedTime> {
+        PublishedTime::parse(&self.streamed_date, Utc::now())
+    }
+
+    /// The timestamp of [`Stream::published_time`].
     ///
     /// # Note
     ///
-    /// The calculated timestamp is an approximation and may not be exact, especially
-    /// for longer time periods like months or years due to varying month lengths and leap years.
+    /// Relative times are approximations, only as precise as their unit.
     pub fn timestamp_from_time_ago(&self) -> Option<DateTime<Utc>> {
-        let now = Utc::now();
-
-        if let Some(captures) = TIME_AGO_REGEX.captures(&self.streamed_date) {
-            let amount: i64 = captures[1].parse().unwrap();
-            let unit = &captures[2];
-
-            let duration = match unit {
-                "second" => Duration::seconds(amount),
-                "minute" => Duration::minutes(amount),
-                "hour" => Duration::hours(amount),
-                "day" => Duration::days(amount),
-                "week" => Duration::weeks(amount),
-                "month" => Duration::days(amount * 30), // Approximation
-                "year" => Duration::days(amount * 365), // Approximation
-                _ => return None,
-            };
-
-            Some(now - duration)
-        } else {
-            None
-        }
+        self.published_time().map(|time| time.timestamp)
     }
 
-    /// Attempts to determine the StreamCategory from a given title.
-    ///
-    /// This function searches for specific keywords in the title to identify
-    /// the appropriate StreamCategory.
-    pub fn category(&self) -> StreamCategory {
-        if self.title.to_lowercase().contains("national assembly") {
-            return StreamCategory::NationalAssembly;
-        }
-        if self.title.to_lowercase().contains("senate") {
-            return StreamCategory::Senate;
-        }
-        StreamCategory::Other
+    /// Whether YouTube lists the stream as having closed captions.
+    pub fn has_captions(&self) -> bool {
+        self.badges.iter().any(|badge| badge == "CC")
     }
-}
 
-#[derive(Debug)]
-pub enum StreamCategory {
-    NationalAssembly,
-    Senate,
-    Other,
-}
+    /// Parses the house, date, session and kind of sitting from the stream's title.
+    pub fn sitting_info(&self) -> SittingInfo {
+        SittingInfo::from_title(&self.title)
+    }
 
-impl Display for StreamCategory {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            StreamCategory::NationalAssembly => write!(f, "National Assembly"),
-            StreamCategory::Senate => write!(f, "Senate"),
-            StreamCategory::Other => write!(f, "Other"),
-        }
+    /// The category the stream was discovered under, or else the one its title names, see
+    /// [`StreamCategory::from_title`].
+    pub fn category(&self) -> StreamCategory {
+        self.category
+            .clone()
+            .unwrap_or_else(|| StreamCategory::from_title(&self.title))
     }
 }
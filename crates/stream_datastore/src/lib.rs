@@ -9,5 +9,8 @@
 mod domain;
 mod store;
 
-pub use domain::{Stream, StreamCategory};
+pub use domain::{
+    House, LiveStatus, PublishedTime, ScheduledStream, Session, SittingInfo, SittingKind, Stream,
+    StreamCategory, TimePrecision,
+};
 pub use store::DataStore;
@@ -1,9 +1,9 @@
-use crate::domain::TIME_AGO_REGEX;
-use crate::Stream;
+use crate::{ScheduledStream, Stream};
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use itertools::{Either, Itertools};
 use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
-use std::{collections::HashSet, sync::LazyLock};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 pub struct DataStore {
@@ -16,7 +16,6 @@ impl DataStore {
     /// Establish connection to database and create the streams table
     /// if not exists
     pub async fn init(database_url: &str) -> anyhow::Result<Self> {
-        LazyLock::force(&TIME_AGO_REGEX);
         let pool = PgPoolOptions::new()
             .max_connections(5)
             .connect(database_url)
@@ -63,8 +62,8 @@ impl DataStore {
     ) -> anyhow::Result<BulkInsertResult> {
         let (valid_streams, invalid_stream_date_errors): (Vec<_>, Vec<_>) =
             streams.iter().partition_map(|stream| {
-                if let Some(timestamp) = stream.timestamp_from_time_ago() {
-                    Either::Left((stream.clone(), timestamp))
+                if let Some(published_time) = stream.published_time() {
+                    Either::Left((stream.clone(), published_time))
                 } else {
                     let reason = InsertFailReason::InvalidStreamedDate {
                         malformed_date: stream.streamed_date.clone(),
@@ -76,7 +75,17 @@ impl DataStore {
                 }
             });
 
-        let (video_ids, title, view_counts, streamed_dates, durations, summaries, timestamp_md): (
+        let (
+            video_ids,
+            title,
+            view_counts,
+            streamed_dates,
+            durations,
+            summaries,
+            timestamp_md,
+            sources,
+        ): (
+            Vec<_>,
             Vec<_>,
             Vec<_>,
             Vec<_>,
@@ -86,23 +95,68 @@ impl DataStore {
             Vec<_>,
         ) = valid_streams
             .iter()
-            .map(|(stream, stream_date)| {
+            .map(|(stream, published_time)| {
                 (
                     stream.video_id.clone(),
                     stream.title.clone(),
                     stream.view_count.clone(),
-                    *stream_date,
+                    published_time.timestamp,
                     stream.duration.clone(),
                     stream.summary_md.clone(),
                     stream.timestamp_md.clone(),
+                    stream.source.clone(),
+                )
+            })
+            .multiunzip();
+
+        let (precisions, categories, houses, sitting_dates, sitting_sessions, sitting_kinds): (
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+        ) = valid_streams
+            .iter()
+            .map(|(stream, published_time)| {
+                let sitting = stream.sitting_info();
+                (
+                    published_time.precision.as_str(),
+                    stream.category(),
+                    sitting.house.as_str(),
+                    sitting.date,
+                    sitting.session.map(|session| session.as_str()),
+                    sitting.sitting_kind.map(|kind| kind.as_str()),
+                )
+            })
+            .multiunzip();
+
+        // postgres can't take an array of arrays of differing lengths, so each stream's badges
+        // are sent as a JSON array and unpacked by the query
+        let (thumbnail_urls, description_snippets, badges, duration_labels): (
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+        ) = valid_streams
+            .iter()
+            .map(|(stream, _)| {
+                (
+                    stream.thumbnail_url.clone(),
+                    stream.description_snippet.clone(),
+                    serde_json::Value::from(stream.badges.clone()).to_string(),
+                    stream.duration_label.clone(),
                 )
             })
             .multiunzip();
 
         let pg_result = sqlx::query(
             "
-            INSERT INTO streams (video_id, title, view_count,stream_timestamp, duration, summary_md, timestamp_md)
-            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::timestamptz[], $5::text[], $6::text[], $7::text[]) ON CONFLICT DO NOTHING
+            INSERT INTO streams (video_id, title, view_count,stream_timestamp, duration, summary_md, timestamp_md, source, stream_timestamp_precision, category, house, sitting_date, sitting_session, sitting_kind, thumbnail_url, description_snippet, badges, duration_label)
+            SELECT video_id, title, view_count, stream_timestamp, duration, summary_md, timestamp_md, source, stream_timestamp_precision, category, house, sitting_date, sitting_session, sitting_kind, thumbnail_url, description_snippet, ARRAY(SELECT jsonb_array_elements_text(badges::jsonb)), duration_label
+            FROM UNNEST($1::text[], $2::text[], $3::text[], $4::timestamptz[], $5::text[], $6::text[], $7::text[], $8::text[], $9::text[], $10::text[], $11::text[], $12::date[], $13::text[], $14::text[], $15::text[], $16::text[], $17::text[], $18::text[])
+                AS t(video_id, title, view_count, stream_timestamp, duration, summary_md, timestamp_md, source, stream_timestamp_precision, category, house, sitting_date, sitting_session, sitting_kind, thumbnail_url, description_snippet, badges, duration_label)
+            ON CONFLICT DO NOTHING
             "
         )
         .bind(&video_ids[..])
@@ -112,6 +166,17 @@ impl DataStore {
         .bind(&durations[..])
         .bind(&summaries[..])
         .bind(&timestamp_md[..])
+        .bind(&sources[..])
+        .bind(&precisions[..])
+        .bind(&categories[..])
+        .bind(&houses[..])
+        .bind(&sitting_dates[..])
+        .bind(&sitting_sessions[..])
+        .bind(&sitting_kinds[..])
+        .bind(&thumbnail_urls[..])
+        .bind(&description_snippets[..])
+        .bind(&badges[..])
+        .bind(&duration_labels[..])
         .execute(&self.pool)
         .await
         .inspect_err(|err| {
@@ -136,6 +201,107 @@ impl DataStore {
             failed_inserts: invalid_stream_date_errors,
         })
     }
+
+    /// Records upcoming and live streams, updating the ones already known.
+    ///
+    /// A known scheduled start is kept when a stream that went live no longer announces it.
+    #[tracing::instrument(skip(self, streams))]
+    pub async fn upsert_scheduled_streams(
+        &self,
+        streams: &[ScheduledStream],
+    ) -> anyhow::Result<u64> {
+        let (video_ids, titles, statuses, scheduled_starts, sources): (
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+        ) = streams
+            .iter()
+            .map(|stream| {
+                (
+                    stream.video_id.clone(),
+                    stream.title.clone(),
+                    stream.status.as_str(),
+                    stream.scheduled_start,
+                    stream.source.clone(),
+                )
+            })
+            .multiunzip();
+
+        let pg_result = sqlx::query(
+            "
+            INSERT INTO scheduled_streams (video_id, title, status, scheduled_start, source)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::timestamptz[], $5::text[])
+            ON CONFLICT (video_id) DO UPDATE SET
+                title = EXCLUDED.title,
+                status = EXCLUDED.status,
+                scheduled_start = COALESCE(EXCLUDED.scheduled_start, scheduled_streams.scheduled_start),
+                source = COALESCE(EXCLUDED.source, scheduled_streams.source),
+                updated_at = NOW()
+            ",
+        )
+        .bind(&video_ids[..])
+        .bind(&titles[..])
+        .bind(&statuses[..])
+        .bind(&scheduled_starts[..])
+        .bind(&sources[..])
+        .execute(&self.pool)
+        .await
+        .inspect_err(|err| tracing::error!(error = ?err, "Failed to upsert scheduled streams"))
+        .context("Failed to upsert scheduled streams")?;
+
+        Ok(pg_result.rows_affected())
+    }
+
+    /// Scheduled streams that are live, or were set to start by `now`, and may have ended since.
+    pub async fn get_due_scheduled_streams(
+        &self,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ScheduledStream>> {
+        sqlx::query_as::<_, ScheduledStream>(
+            "
+            SELECT video_id, title, status, scheduled_start, source FROM scheduled_streams
+            WHERE status = 'live' OR scheduled_start <= $1
+            ORDER BY scheduled_start
+            ",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .inspect_err(|e| tracing::error!(error = ?e, "Failed to fetch due scheduled streams"))
+        .context("Failed to fetch due scheduled streams")
+    }
+
+    /// Forgets scheduled streams, e.g. once they've been archived.
+    pub async fn delete_scheduled_streams(&self, video_ids: &[&str]) -> anyhow::Result<u64> {
+        let pg_result = sqlx::query("DELETE FROM scheduled_streams WHERE video_id = ANY($1)")
+            .bind(video_ids)
+            .execute(&self.pool)
+            .await
+            .inspect_err(|e| tracing::error!(error = ?e, "Failed to delete scheduled streams"))
+            .context("Failed to delete scheduled streams")?;
+
+        Ok(pg_result.rows_affected())
+    }
+
+    /// Forgets scheduled streams discovery hasn't seen since `not_seen_since`, which were
+    /// cancelled, made private or archived where discovery no longer looks.
+    pub async fn delete_stale_scheduled_streams(
+        &self,
+        not_seen_since: DateTime<Utc>,
+    ) -> anyhow::Result<u64> {
+        let pg_result = sqlx::query("DELETE FROM scheduled_streams WHERE updated_at < $1")
+            .bind(not_seen_since)
+            .execute(&self.pool)
+            .await
+            .inspect_err(
+                |e| tracing::error!(error = ?e, "Failed to delete stale scheduled streams"),
+            )
+            .context("Failed to delete stale scheduled streams")?;
+
+        Ok(pg_result.rows_affected())
+    }
 }
 
 #[derive(Debug)]
@@ -157,10 +323,11 @@ pub enum InsertFailReason {
 
 #[cfg(test)]
 mod tests {
-    use chrono::Utc;
+    use chrono::{SubsecRound, Utc};
     use sqlx::PgPool;
 
     use super::*;
+    use crate::{LiveStatus, StreamCategory};
 
     #[sqlx::test(migrator = "MIGRATOR")]
     async fn test_bulk_insert_and_check_existing_streams_works(pool: PgPool) {
@@ -175,6 +342,14 @@ mod tests {
                 duration: chrono::Duration::seconds(3600).to_string(),
                 summary_md: Some("This is a test video summary".to_owned()),
                 timestamp_md: Some(Utc::now().to_string()),
+                source: Some("Parliament of Kenya".to_string()),
+                thumbnail_url: Some(
+                    "https://i.ytimg.com/vi/test_video_1/maxresdefault.jpg".to_string(),
+                ),
+                description_snippet: Some("Proceedings of the sitting".to_string()),
+                badges: vec!["CC".to_string(), "New".to_string()],
+                duration_label: Some("1 hour".to_string()),
+                category: None,
             },
             Stream {
                 video_id: "test_video_2".to_string(),
@@ -184,6 +359,10 @@ mod tests {
                 duration: chrono::Duration::seconds(7200).to_string(),
                 summary_md: Some("This is another test video summary".to_owned()),
                 timestamp_md: Some(Utc::now().to_string()),
+                source: Some("Senate Committees".to_string()),
+                // e.g. the default of a source the title doesn't name a category for
+                category: Some(StreamCategory::Senate),
+                ..Default::default()
             },
             Stream {
                 video_id: "test_video_3".to_string(),
@@ -193,6 +372,8 @@ mod tests {
                 duration: chrono::Duration::seconds(1800).to_string(),
                 summary_md: None,
                 timestamp_md: None,
+                source: None,
+                ..Default::default()
             },
             // This stream has an invalid streamed_date format
             Stream {
@@ -203,6 +384,8 @@ mod tests {
                 duration: chrono::Duration::seconds(600).to_string(),
                 summary_md: Some("This stream has an invalid date format".to_owned()),
                 timestamp_md: Some(Utc::now().to_string()),
+                source: None,
+                ..Default::default()
             },
         ];
 
@@ -236,5 +419,98 @@ mod tests {
         for invalid_stream in result.failed_inserts {
             assert!(expected_invalid_streams.contains(&invalid_stream.video_id));
         }
+
+        // verify that categories are written by rust and read back
+        let category: StreamCategory =
+            sqlx::query_scalar("SELECT category FROM streams WHERE video_id = $1")
+                .bind("test_video_1")
+                .fetch_one(&datastore.pool)
+                .await
+                .unwrap();
+        assert_eq!(category, StreamCategory::Other);
+        let category: StreamCategory =
+            sqlx::query_scalar("SELECT category FROM streams WHERE video_id = $1")
+                .bind("test_video_2")
+                .fetch_one(&datastore.pool)
+                .await
+                .unwrap();
+        assert_eq!(category, StreamCategory::Senate);
+
+        // verify that badges are unpacked into an array, and missing ones into an empty one
+        let previews: Vec<(String, Option<String>, Vec<String>)> = sqlx::query_as(
+            "SELECT video_id, thumbnail_url, badges FROM streams WHERE video_id = ANY($1) ORDER BY video_id",
+        )
+        .bind(["test_video_1", "test_video_2"])
+        .fetch_all(&datastore.pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            previews,
+            vec![
+                (
+                    "test_video_1".to_string(),
+                    streams[0].thumbnail_url.clone(),
+                    streams[0].badges.clone(),
+                ),
+                ("test_video_2".to_string(), None, vec![]),
+            ]
+        );
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn test_scheduled_streams_become_due(pool: PgPool) {
+        let datastore = DataStore { pool };
+        // postgres keeps timestamps to the microsecond
+        let now = Utc::now().trunc_subsecs(6);
+
+        let upcoming = ScheduledStream {
+            video_id: "upcoming".to_string(),
+            title: "Upcoming Sitting".to_string(),
+            status: LiveStatus::Upcoming,
+            scheduled_start: Some(now + chrono::Duration::hours(2)),
+            source: Some("Parliament of Kenya".to_string()),
+        };
+        let started = ScheduledStream {
+            video_id: "started".to_string(),
+            scheduled_start: Some(now - chrono::Duration::minutes(5)),
+            ..upcoming.clone()
+        };
+        datastore
+            .upsert_scheduled_streams(&[upcoming.clone(), started.clone()])
+            .await
+            .unwrap();
+
+        let due = datastore.get_due_scheduled_streams(now).await.unwrap();
+        assert_eq!(due, vec![started]);
+
+        // going live keeps the announced start time
+        let live = ScheduledStream {
+            status: LiveStatus::Live,
+            scheduled_start: None,
+            ..upcoming.clone()
+        };
+        datastore.upsert_scheduled_streams(&[live]).await.unwrap();
+
+        let due = datastore.get_due_scheduled_streams(now).await.unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[1].status, LiveStatus::Live);
+        assert_eq!(due[1].scheduled_start, upcoming.scheduled_start);
+
+        datastore
+            .delete_scheduled_streams(&["started"])
+            .await
+            .unwrap();
+        assert_eq!(
+            datastore
+                .delete_stale_scheduled_streams(now + chrono::Duration::minutes(1))
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(datastore
+            .get_due_scheduled_streams(now)
+            .await
+            .unwrap()
+            .is_empty());
     }
 }
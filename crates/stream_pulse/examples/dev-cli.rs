@@ -1,6 +1,17 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::{NaiveDate, Utc};
 use clap::{Parser, Subcommand};
 use futures::FutureExt;
-use stream_pulse::{fetch_and_process_streams, tracing::init_tracing_subscriber};
+use serde_json::Value;
+use stream_pulse::{
+    backfill_streams, fetch_and_process_streams,
+    fixtures::{diff_structure, list_fixtures, snapshot, FixtureFormat, FIXTURES_DIR},
+    tracing::init_tracing_subscriber,
+    transcribe_stream_section, BrowseRequest, InnerTubeClient, Source,
+};
+use ytdlp_bindings::TimeRange;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -21,6 +32,42 @@ pub enum Commands {
         max_streams: usize,
     },
 
+    /// Process past sittings
+    ///
+    /// Walks back through the channel's streams to the given date, e.g. to fill in sittings from
+    /// before the cron job was deployed.
+    Backfill {
+        /// Earliest streaming date to go back to (e.g. 2025-01-01)
+        since: NaiveDate,
+        /// Maximum number of streams to handle in this run, oldest first (default: 3)
+        #[arg(long, default_value_t = 3)]
+        max_streams: usize,
+    },
+
+    /// Transcribe a section of a stream
+    ///
+    /// Downloads only the requested section and prints its transcript, e.g. to redo a garbled region.
+    TranscribeSection {
+        /// The YouTube video ID of the stream (e.g. p40gmygQL2c)
+        video_id: String,
+        /// The section to transcribe as `start-end` (e.g. 01:10:00-01:25:00)
+        section: TimeRange,
+    },
+
+    /// Check the parser against the live channel page
+    ///
+    /// Browses the channel's streams tab as the pipeline does, parses it and diffs its structure
+    /// against the newest `streams` fixture, to catch YouTube layout changes before the cron
+    /// job does.
+    DiffFixtures {
+        /// Directory of the fixtures to compare with
+        #[arg(long, default_value = FIXTURES_DIR)]
+        fixtures: PathBuf,
+        /// Save the browse response as a new fixture, along with its snapshot
+        #[arg(long)]
+        save: bool,
+    },
+
     /// Generate navigation timestamps for a stream
     ///
     /// Provide a YouTube video ID to generate highlight timestamps from its transcript.
@@ -48,6 +95,20 @@ async fn main() -> anyhow::Result<()> {
             }
         }
 
+        Commands::Backfill { since, max_streams } => {
+            let since = since.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+            backfill_streams(since, max_streams).await?;
+        }
+
+        Commands::TranscribeSection { video_id, section } => {
+            let transcript = transcribe_stream_section(&video_id, section).await?;
+            println!("{transcript}");
+        }
+
+        Commands::DiffFixtures { fixtures, save } => {
+            diff_fixtures(&fixtures, save).await?;
+        }
+
         Commands::GenerateStreamTimestamps { video_id } => {
             todo!(
                 "Implement generate_stream_timestamps for video_id = {}",
@@ -58,3 +119,55 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+async fn diff_fixtures(fixtures_dir: &Path, save: bool) -> anyhow::Result<()> {
+    let source = Source::parliament_of_kenya();
+    let fixture = list_fixtures(fixtures_dir)?
+        .into_iter()
+        .rfind(|fixture| fixture.source == source)
+        .context("No streams fixture to compare with")?;
+    let (fixture_json, fixture_page) = fixture.parse()?;
+
+    let innertube = InnerTubeClient::discover(reqwest::Client::new(), &source.url()).await;
+    let endpoint = source.browse_endpoint(&innertube).await?;
+    let json = innertube
+        .browse::<Value>(&BrowseRequest::Endpoint(endpoint))
+        .await?;
+
+    // only the page contents are parsed, the rest changes too often to be of interest
+    let diff = diff_structure(&fixture_json, &json);
+    let in_contents = |path: &&String| path.starts_with("contents.");
+    println!("Compared {} with {}", source.url(), fixture.path.display());
+    for path in diff.added.iter().filter(in_contents) {
+        println!("+ {path}");
+    }
+    for path in diff.removed.iter().filter(in_contents) {
+        println!("- {path}");
+    }
+
+    let page = source.parse_first_page(&json)?;
+    println!(
+        "Parsed {} streams and {} upcoming or live ones (fixture: {} and {}), continuation: {}",
+        page.streams.len(),
+        page.scheduled.len(),
+        fixture_page.streams.len(),
+        fixture_page.scheduled.len(),
+        page.continuation.is_some()
+    );
+
+    if save {
+        let path = fixtures_dir.join(format!(
+            "{}_streams.{}",
+            Utc::now().format("%Y-%m-%d"),
+            FixtureFormat::Browse.extension()
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(&json)? + "\n")?;
+        std::fs::write(
+            path.with_extension("snap.json"),
+            serde_json::to_string_pretty(&snapshot(&page))? + "\n",
+        )?;
+        println!("Saved {}", path.display());
+    }
+
+    Ok(())
+}
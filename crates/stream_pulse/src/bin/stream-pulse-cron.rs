@@ -1,9 +1,13 @@
 use std::sync::{Arc, Mutex};
 
-use stream_pulse::{start_cron, start_server, tracing::init_tracing_subscriber, AppState};
+use stream_pulse::{
+    start_cron, start_server, tracing::init_tracing_subscriber, ytdlp_version, AppState,
+};
 
 /// Every 4 hours
 const DEFAULT_CRON_SCHEDULE: &str = "0 0 */4 * * *";
+/// Every 10 minutes
+const DEFAULT_LIVE_CHECK_SCHEDULE: &str = "0 */10 * * * *";
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> anyhow::Result<()> {
@@ -22,13 +26,21 @@ async fn main() -> anyhow::Result<()> {
 
     let cron_schedule =
         std::env::var("CRON_SCHEDULE").unwrap_or_else(|_| DEFAULT_CRON_SCHEDULE.to_string());
+    let live_check_schedule = std::env::var("LIVE_CHECK_SCHEDULE")
+        .unwrap_or_else(|_| DEFAULT_LIVE_CHECK_SCHEDULE.to_string());
+
+    let ytdlp_version = ytdlp_version()
+        .inspect(|version| tracing::info!(%version, "Using yt-dlp"))
+        .inspect_err(|e| tracing::error!(error = ?e, "Failed to determine yt-dlp version"))
+        .ok();
 
     let app_state = Arc::new(AppState {
         next_tick_for_job: Mutex::new(None),
+        ytdlp_version,
     });
 
     tokio::select! {
-        _ = start_cron(&cron_schedule, app_state.clone()) => {}
+        _ = start_cron(&cron_schedule, &live_check_schedule, app_state.clone()) => {}
         _ = start_server(app_state.clone()) => {}
     }
 
@@ -13,6 +13,10 @@
 //! - Calls [`fetch_and_process_streams`] to perform the full pipeline
 //! - Handles errors and panics gracefully with structured `tracing` logs
 //!
+//! A second job checks every 10 minutes (by default) on streams that were live or scheduled
+//! to start, running the pipeline on them through [`process_ended_streams`] once they're archived.
+//! Only one pipeline run happens at a time, a job skips its tick while the other is running.
+//!
 //! The module also exposes a live status mechanism:
 //! - A background loop updates `AppState.next_tick_for_job` every 5 seconds
 //! - This is used by the HTTP server's `/status` endpoint to report upcoming job ticks
@@ -24,21 +28,31 @@
 //! ## Environment Variables
 //!
 //! - `CRON_SCHEDULE`: Custom cron string (optional, defaults to "0 0 */4 * * *")
+//! - `LIVE_CHECK_SCHEDULE`: Custom cron string for checking on live streams (optional, defaults
+//!   to "0 */10 * * * *")
 //! - `MAX_STREAMS_TO_PROCESS`: Limits how many streams are processed per run
 
-use std::{sync::Arc, time::Duration};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use chrono_tz::Africa::Nairobi;
 use futures::FutureExt;
+use tokio::sync::Mutex;
 use tokio_cron_scheduler::{JobBuilder, JobScheduler};
 use uuid::Uuid;
 
-use crate::fetch_and_process_streams;
+use crate::{fetch_and_process_streams, process_ended_streams};
 
 use super::AppState;
 
-pub async fn start_cron(cron_schedule: &str, app_state: Arc<AppState>) -> anyhow::Result<()> {
-    tracing::info!(%cron_schedule);
+/// Held for the duration of a pipeline run
+static PIPELINE_LOCK: Mutex<()> = Mutex::const_new(());
+
+pub async fn start_cron(
+    cron_schedule: &str,
+    live_check_schedule: &str,
+    app_state: Arc<AppState>,
+) -> anyhow::Result<()> {
+    tracing::info!(%cron_schedule, %live_check_schedule);
 
     let mut scheduler = JobScheduler::new().await?;
 
@@ -51,34 +65,26 @@ pub async fn start_cron(cron_schedule: &str, app_state: Arc<AppState>) -> anyhow
         .with_schedule(cron_schedule)?
         .with_run_async(Box::new(|uuid, _| {
             Box::pin(async move {
-                // Maximum streams that can be processed in a run
-                let max_streams = std::env::var("MAX_STREAMS_TO_PROCESS")
-                    .ok()
-                    .and_then(|v| v.parse::<usize>().ok())
-                    .unwrap_or(3);
-
+                let max_streams = max_streams_to_process();
                 tracing::info!(job_id = %uuid, max_streams, "Running cron job...");
+                run_pipeline(uuid, fetch_and_process_streams(max_streams)).await;
+            })
+        }))
+        .build()?;
 
-                let result = std::panic::AssertUnwindSafe(fetch_and_process_streams(max_streams))
-                    .catch_unwind()
-                    .await;
-
-                match result {
-                    Ok(Ok(_)) => {
-                        tracing::info!(job_id = %uuid, "Cron job completed successfully");
-                    }
-                    Ok(Err(err)) => {
-                        tracing::error!(job_id = %uuid, error = ?err, "Fetch and process streams pipeline failed");
-                    }
-                    Err(panic_err) => {
-                        tracing::error!(job_id = %uuid, error = ?panic_err, "Fetch and process streams pipeline failed");
-                    }
-                }
+    let live_check_job = JobBuilder::new()
+        .with_timezone(chrono_tz::Africa::Nairobi)
+        .with_cron_job_type()
+        .with_schedule(live_check_schedule)?
+        .with_run_async(Box::new(|uuid, _| {
+            Box::pin(async move {
+                run_pipeline(uuid, process_ended_streams(max_streams_to_process())).await;
             })
         }))
         .build()?;
 
     scheduler.add(job).await?;
+    scheduler.add(live_check_job).await?;
     scheduler.start().await?;
 
     tokio::select! {
@@ -92,6 +98,36 @@ pub async fn start_cron(cron_schedule: &str, app_state: Arc<AppState>) -> anyhow
     Ok(())
 }
 
+/// Maximum streams that can be processed in a run
+fn max_streams_to_process() -> usize {
+    std::env::var("MAX_STREAMS_TO_PROCESS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(3)
+}
+
+/// Runs a pipeline unless another run is still in progress, logging its outcome.
+async fn run_pipeline(job_id: Uuid, pipeline: impl Future<Output = anyhow::Result<()>>) {
+    let Ok(_guard) = PIPELINE_LOCK.try_lock() else {
+        tracing::info!(%job_id, "A pipeline run is already in progress, skipping");
+        return;
+    };
+
+    let result = std::panic::AssertUnwindSafe(pipeline).catch_unwind().await;
+
+    match result {
+        Ok(Ok(_)) => {
+            tracing::info!(%job_id, "Cron job completed successfully");
+        }
+        Ok(Err(err)) => {
+            tracing::error!(%job_id, error = ?err, "Fetch and process streams pipeline failed");
+        }
+        Err(panic_err) => {
+            tracing::error!(%job_id, error = ?panic_err, "Fetch and process streams pipeline failed");
+        }
+    }
+}
+
 async fn check_time_till_next_job(
     scheduler: &mut JobScheduler,
     job_id: Uuid,
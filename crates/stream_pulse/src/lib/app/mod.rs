@@ -10,4 +10,6 @@ pub mod server;
 #[derive(Debug)]
 pub struct AppState {
     pub next_tick_for_job: Mutex<Option<DateTime<Tz>>>,
+    /// Version reported by the deployed yt-dlp binary, if it could be determined at startup
+    pub ytdlp_version: Option<String>,
 }
@@ -4,30 +4,59 @@
 //!
 //! ## Endpoints
 //!
-//! - `GET /status`: Returns the next scheduled cron job tick as an ISO 8601 timestamp
-//!   and a `healthy` flag indicating if the service is up.
+//! - `GET /status`: Returns the next scheduled cron job tick as an ISO 8601 timestamp,
+//!   a `healthy` flag indicating if the service is up and the deployed yt-dlp version.
 //!
 //! Example response:
 //!
 //! ```json
 //! {
 //!   "healthy": true,
-//!   "next_tick": "2025-07-03T18:00:00+03:00"
+//!   "next_tick": "2025-07-03T18:00:00+03:00",
+//!   "ytdlp_version": "2025.03.31"
 //! }
 //! ```
 //!
 //! The `next_tick` value is updated every few seconds based on the scheduler state.
+//!
+//! - `GET /captions/{video_id}.vtt` or `GET /captions/{video_id}.srt`: Returns the captions
+//!   generated from a processed stream's transcript, or `404` if there are none.
+//!
+//! - `GET /clips/{video_id}`: Lists the audio clips of a processed stream's notable quotes and
+//!   key moments, or `404` if there are none.
+//!
+//! ```json
+//! [
+//!   {
+//!     "kind": "quote",
+//!     "text": "Development must never come at the expense of human lives.",
+//!     "start_secs": 5421.3,
+//!     "end_secs": 5429.8,
+//!     "file": "quote_00.mp3"
+//!   }
+//! ]
+//! ```
+//!
+//! - `GET /clips/{video_id}/{file}`: Returns one of the listed clips as MP3.
 
 use std::sync::{Arc, LazyLock};
 
-use axum::{extract::State, http::header, routing::get, Json, Router};
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
 use chrono::SecondsFormat;
 use reqwest::Method;
 use serde::Serialize;
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
+use ytdlp_bindings::SubtitleFormat;
 
 use super::AppState;
+use crate::process_stream::{caption_path, clip_manifest_path, clips_path};
 
 pub static ALLOWED_ORIGINS: LazyLock<Vec<header::HeaderValue>> = LazyLock::new(|| {
     vec![
@@ -45,6 +74,9 @@ pub async fn start_server(app_state: Arc<AppState>) -> anyhow::Result<()> {
 
     let app = Router::new()
         .route("/status", get(status))
+        .route("/captions/{file}", get(captions))
+        .route("/clips/{video_id}", get(clips))
+        .route("/clips/{video_id}/{file}", get(clip))
         .with_state(app_state)
         .layer(cors);
 
@@ -62,6 +94,7 @@ pub async fn start_server(app_state: Arc<AppState>) -> anyhow::Result<()> {
 struct StatusResponse {
     healthy: bool,
     next_tick: Option<String>,
+    ytdlp_version: Option<String>,
 }
 
 async fn status(State(app_state): State<Arc<AppState>>) -> Json<StatusResponse> {
@@ -74,5 +107,58 @@ async fn status(State(app_state): State<Arc<AppState>>) -> Json<StatusResponse>
     Json(StatusResponse {
         healthy: true,
         next_tick: next.map(|dt| dt.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        ytdlp_version: app_state.ytdlp_version.clone(),
     })
 }
+
+async fn captions(Path(file): Path<String>) -> impl IntoResponse {
+    let Some((video_id, format)) = file
+        .rsplit_once('.')
+        .and_then(|(video_id, ext)| Some((video_id, SubtitleFormat::from_extension(ext)?)))
+    else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    if !is_safe_file_stem(video_id) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match tokio::fs::read_to_string(caption_path(video_id, format)).await {
+        Ok(captions) => ([(header::CONTENT_TYPE, format.content_type())], captions).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn clips(Path(video_id): Path<String>) -> impl IntoResponse {
+    if !is_safe_file_stem(&video_id) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match tokio::fs::read(clip_manifest_path(&video_id)).await {
+        Ok(manifest) => ([(header::CONTENT_TYPE, "application/json")], manifest).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn clip(Path((video_id, file)): Path<(String, String)>) -> impl IntoResponse {
+    let Some(stem) = file.strip_suffix(".mp3") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !is_safe_file_stem(&video_id) || !is_safe_file_stem(stem) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match tokio::fs::read(clips_path(&video_id).join(&file)).await {
+        Ok(clip) => ([(header::CONTENT_TYPE, "audio/mpeg")], clip).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Video ids and clip names are plain base64url, anything else could escape the served
+/// directories
+fn is_safe_file_stem(stem: &str) -> bool {
+    !stem.is_empty()
+        && stem
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
@@ -3,6 +3,11 @@
 pub enum Error {
     #[error("ParseError: {0}")]
     ParseError(&'static str),
+    /// YouTube changed the structure of its data, as opposed to it just having nothing for us
+    #[error("Unexpected structure at {path}: {reason}")]
+    StructureChanged { path: String, reason: String },
+    #[error("Request Error: {0}")]
+    RequestError(#[from] reqwest::Error),
     #[error("Deserialization Error: {0}")]
     DeserializationError(#[from] serde_json::Error),
     #[error(transparent)]
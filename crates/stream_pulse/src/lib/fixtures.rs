@@ -0,0 +1,326 @@
+//! # Parser Fixtures
+//!
+//! Channel and playlist pages kept under `fixtures/`, each alongside a snapshot of the streams
+//! the parser finds in it, so that changes to the parser are checked against the layouts
+//! YouTube has served over time.
+//!
+//! Fixtures are named `{date}_{kind}.html` for a page's HTML and `{date}_{kind}.browse.json`
+//! for the InnerTube browse response the pipeline requests instead, `kind` being the page they
+//! stand for: `streams` or `videos` for those channel tabs, `playlist` for a playlist.
+//! Their snapshots are the `{date}_{kind}.snap.json` and `{date}_{kind}.browse.snap.json` files
+//! next to them.
+//!
+//! The same pieces let the dev CLI compare a freshly browsed page with the newest fixture,
+//! catching parser drift before the cron job runs into it.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde_json::{json, Value};
+
+use crate::{
+    extract_json_from_script,
+    parser::{ChannelTab, StreamsPage},
+    sources::{Source, SourceKind},
+};
+
+/// Where the fixtures are kept in this crate
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/// What a fixture holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureFormat {
+    /// A page's HTML, with its `ytInitialData` in a script
+    Page,
+    /// An InnerTube browse response
+    Browse,
+}
+
+impl FixtureFormat {
+    /// The extension of fixtures of this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            FixtureFormat::Page => "html",
+            FixtureFormat::Browse => "browse.json",
+        }
+    }
+}
+
+/// A fixture page or browse response, and the source it stands for
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub path: PathBuf,
+    pub source: Source,
+    pub format: FixtureFormat,
+}
+
+impl Fixture {
+    /// The fixture at `path`, with a stand-in source matching the kind of page in its name.
+    pub fn new(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let (stem, format) = [FixtureFormat::Browse, FixtureFormat::Page]
+            .into_iter()
+            .find_map(|format| {
+                let stem = name.strip_suffix(format.extension())?.strip_suffix('.')?;
+                Some((stem, format))
+            })
+            .with_context(|| {
+                format!(
+                    "Fixture {} is neither a page nor a browse response",
+                    path.display()
+                )
+            })?;
+        let kind = stem
+            .rsplit_once('_')
+            .map(|(_, kind)| kind)
+            .with_context(|| format!("Fixture {} isn't named {{date}}_{{kind}}", path.display()))?;
+
+        let default = Source::parliament_of_kenya();
+        let kind = match kind {
+            "streams" => default.kind,
+            "videos" => SourceKind::Channel {
+                handle: "@ParliamentofKenyaChannel".to_string(),
+                tab: ChannelTab::Videos,
+            },
+            "playlist" => SourceKind::Playlist {
+                id: "fixture".to_string(),
+            },
+            kind => anyhow::bail!("Unknown kind of fixture: {kind}"),
+        };
+
+        Ok(Fixture {
+            path,
+            source: Source { kind, ..default },
+            format,
+        })
+    }
+
+    /// The snapshot file next to the fixture
+    pub fn snapshot_path(&self) -> PathBuf {
+        self.path.with_extension("snap.json")
+    }
+
+    /// The fixture's `ytInitialData` or browse response, and the streams the parser finds in it
+    pub fn parse(&self) -> anyhow::Result<(Value, StreamsPage)> {
+        let document = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read fixture {}", self.path.display()))?;
+        match self.format {
+            FixtureFormat::Page => parse_document(&document, &self.source),
+            FixtureFormat::Browse => {
+                let json = serde_json::from_str::<Value>(&document)
+                    .with_context(|| format!("Invalid browse response {}", self.path.display()))?;
+                let page = self.source.parse_first_page(&json)?;
+                Ok((json, page))
+            }
+        }
+    }
+}
+
+/// Lists the fixtures in `dir`, oldest first.
+pub fn list_fixtures(dir: impl AsRef<Path>) -> anyhow::Result<Vec<Fixture>> {
+    let dir = dir.as_ref();
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to list fixtures in {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".html") || name.ends_with(".browse.json"))
+    });
+    paths.sort();
+
+    paths.into_iter().map(Fixture::new).collect()
+}
+
+/// Extracts the `ytInitialData` of a page.
+pub fn initial_data(document: &str) -> anyhow::Result<Value> {
+    Ok(extract_json_from_script::<Value>(document)?)
+}
+
+/// Extracts the `ytInitialData` of a page and parses its first page of streams.
+pub fn parse_document(document: &str, source: &Source) -> anyhow::Result<(Value, StreamsPage)> {
+    let json = initial_data(document)?;
+    let page = source.parse_first_page(&json)?;
+
+    Ok((json, page))
+}
+
+/// What's compared between a page and its snapshot
+pub fn snapshot(page: &StreamsPage) -> Value {
+    json!({
+        "continuation": page.continuation,
+        "streams": page.streams.iter().map(|stream| json!({
+            "video_id": stream.video_id,
+            "title": stream.title,
+            "view_count": stream.view_count,
+            "streamed_date": stream.streamed_date,
+            "duration": stream.duration,
+            "thumbnail_url": stream.thumbnail_url,
+            "description_snippet": stream.description_snippet,
+            "badges": stream.badges,
+            "duration_label": stream.duration_label,
+        })).collect::<Vec<_>>(),
+        "scheduled": page.scheduled.iter().map(|stream| json!({
+            "video_id": stream.video_id,
+            "title": stream.title,
+            "status": stream.status.as_str(),
+            "scheduled_start": stream.scheduled_start.map(|start| start.to_rfc3339()),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// Every key path in `json`, array elements collapsed into `[]`, e.g.
+/// `contents.twoColumnBrowseResultsRenderer.tabs[].tabRenderer.title`.
+pub fn structure_paths(json: &Value) -> BTreeSet<String> {
+    fn collect(value: &Value, path: String, paths: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    collect(value, path, paths);
+                }
+            }
+            Value::Array(items) if !items.is_empty() => {
+                for item in items {
+                    collect(item, format!("{path}[]"), paths);
+                }
+            }
+            _ => {
+                paths.insert(path);
+            }
+        }
+    }
+
+    let mut paths = BTreeSet::new();
+    collect(json, String::new(), &mut paths);
+    paths
+}
+
+/// Key paths that appeared or disappeared between two `ytInitialData`s
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StructureDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl StructureDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Compares the structure of `before` and `after`, ignoring the values in them.
+pub fn diff_structure(before: &Value, after: &Value) -> StructureDiff {
+    let before = structure_paths(before);
+    let after = structure_paths(after);
+
+    StructureDiff {
+        added: after.difference(&before).cloned().collect(),
+        removed: before.difference(&after).cloned().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Set `UPDATE_SNAPSHOTS=1` to rewrite the snapshots after an intended parser change
+    #[test]
+    fn fixtures_match_snapshots() {
+        let update = std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1");
+        let fixtures = list_fixtures(FIXTURES_DIR).unwrap();
+        assert!(!fixtures.is_empty());
+
+        for fixture in fixtures {
+            let (_, page) = fixture.parse().unwrap();
+            let actual = snapshot(&page);
+            let snapshot_path = fixture.snapshot_path();
+
+            if update {
+                let json = serde_json::to_string_pretty(&actual).unwrap() + "\n";
+                std::fs::write(&snapshot_path, json).unwrap();
+                continue;
+            }
+
+            let expected = std::fs::read_to_string(&snapshot_path)
+                .map(|json| serde_json::from_str::<Value>(&json).unwrap())
+                .unwrap_or_else(|e| panic!("Missing snapshot {}: {e}", snapshot_path.display()));
+            assert_eq!(
+                actual,
+                expected,
+                "{} no longer matches its snapshot",
+                fixture.path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn tells_pages_and_browse_responses_apart() {
+        let page = Fixture::new("fixtures/2025-06-24_playlist.html").unwrap();
+        assert_eq!(page.format, FixtureFormat::Page);
+        assert!(matches!(page.source.kind, SourceKind::Playlist { .. }));
+        assert_eq!(
+            page.snapshot_path(),
+            Path::new("fixtures/2025-06-24_playlist.snap.json")
+        );
+
+        let browse = Fixture::new("fixtures/2025-06-24_streams.browse.json").unwrap();
+        assert_eq!(browse.format, FixtureFormat::Browse);
+        assert_eq!(browse.source, Source::parliament_of_kenya());
+        assert_eq!(
+            browse.snapshot_path(),
+            Path::new("fixtures/2025-06-24_streams.browse.snap.json")
+        );
+
+        assert!(Fixture::new("fixtures/2025-06-24_streams.snap.json").is_err());
+        assert!(Fixture::new("fixtures/README.md").is_err());
+    }
+
+    #[test]
+    fn diffs_structure_but_not_values() {
+        let before = json!({
+            "tabs": [
+                { "tabRenderer": { "title": "Home" } },
+                { "tabRenderer": { "title": "Live", "content": { "richGridRenderer": {} } } }
+            ],
+            "trackingParams": "CAAQhGciEwjL"
+        });
+        let after = json!({
+            "tabs": [
+                { "tabRenderer": { "title": "Shorts" } },
+                { "tabRenderer": { "title": "Live", "content": { "richGridRenderer": {} } } }
+            ],
+            "trackingParams": "CAAQhGciEwjM",
+            "header": { "pageHeaderRenderer": {} }
+        });
+        assert_eq!(
+            structure_paths(&before),
+            BTreeSet::from([
+                "tabs[].tabRenderer.title".to_string(),
+                "tabs[].tabRenderer.content.richGridRenderer".to_string(),
+                "trackingParams".to_string(),
+            ])
+        );
+        assert!(diff_structure(&before, &before).is_empty());
+
+        assert_eq!(
+            diff_structure(&before, &after),
+            StructureDiff {
+                added: vec!["header.pageHeaderRenderer".to_string()],
+                removed: vec![],
+            }
+        );
+    }
+}
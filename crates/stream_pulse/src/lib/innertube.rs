@@ -0,0 +1,387 @@
+//! # InnerTube Client
+//!
+//! A small client for InnerTube, the API YouTube's own web client browses channels and
+//! playlists with. Its `browse` responses share the structure of a page's `ytInitialData`
+//! without having to be scraped out of the page's HTML.
+//!
+//! Requests identify as the client described by a [`ClientContext`], the web client by default,
+//! which can be configured with:
+//!
+//! - `INNERTUBE_CLIENT_VERSION`: version of the web client, e.g. `2.20250620.01.00`, otherwise
+//!   read from the `ytcfg` of a page YouTube serves, see [`InnerTubeClient::discover`]
+//! - `INNERTUBE_HL`: language of the responses, `en` by default
+//! - `INNERTUBE_GL`: region of the responses, `KE` by default
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    error::Error,
+    parser::extract_innertube_client_version,
+    types::{BrowseEndpoint, ResolveUrlResponse},
+};
+
+/// Where InnerTube is served from
+pub const INNERTUBE_BASE_URL: &str = "https://www.youtube.com";
+const DEFAULT_CLIENT_NAME: &str = "WEB";
+// Last web client version known to work, for when the current one can't be discovered
+const DEFAULT_CLIENT_VERSION: &str = "2.20250620.01.00";
+
+/// The client InnerTube requests are made as, sent as `context.client`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientContext {
+    pub client_name: String,
+    pub client_version: String,
+    /// Language of the responses, e.g. of the streams' published times
+    pub hl: String,
+    /// Region of the responses
+    pub gl: String,
+}
+
+impl Default for ClientContext {
+    fn default() -> Self {
+        ClientContext {
+            client_name: DEFAULT_CLIENT_NAME.to_string(),
+            client_version: DEFAULT_CLIENT_VERSION.to_string(),
+            hl: "en".to_string(),
+            gl: "KE".to_string(),
+        }
+    }
+}
+
+impl ClientContext {
+    /// The web client, with whatever is set of `INNERTUBE_CLIENT_VERSION`, `INNERTUBE_HL` and
+    /// `INNERTUBE_GL`.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        ClientContext {
+            client_version: env_var("INNERTUBE_CLIENT_VERSION").unwrap_or(default.client_version),
+            hl: env_var("INNERTUBE_HL").unwrap_or(default.hl),
+            gl: env_var("INNERTUBE_GL").unwrap_or(default.gl),
+            ..default
+        }
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// A page to request from the `browse` endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowseRequest {
+    /// The first page of a channel tab or playlist
+    Endpoint(BrowseEndpoint),
+    /// The page after the one that ended with this continuation token
+    Continuation(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct InnerTubeClient {
+    http: reqwest::Client,
+    base_url: String,
+    context: ClientContext,
+}
+
+impl InnerTubeClient {
+    pub fn new(http: reqwest::Client, context: ClientContext) -> Self {
+        InnerTubeClient {
+            http,
+            base_url: INNERTUBE_BASE_URL.to_string(),
+            context,
+        }
+    }
+
+    /// A client as configured by [`ClientContext::from_env`], identifying, unless
+    /// `INNERTUBE_CLIENT_VERSION` sets a version, as the web client version YouTube currently
+    /// serves `page_url` with, e.g. a channel page, as advertised by the page's `ytcfg`.
+    ///
+    /// Falls back to the last version known to work, with a warning, when the page can't be
+    /// fetched or no longer advertises its version.
+    pub async fn discover(http: reqwest::Client, page_url: &str) -> Self {
+        let mut context = ClientContext::from_env();
+        if env_var("INNERTUBE_CLIENT_VERSION").is_none() {
+            match fetch_client_version(&http, page_url, &context.hl).await {
+                Ok(client_version) => context.client_version = client_version,
+                Err(e) => tracing::warn!(
+                    page_url,
+                    error = %e,
+                    fallback = %context.client_version,
+                    "Failed to discover the InnerTube client version"
+                ),
+            }
+        }
+
+        InnerTubeClient::new(http, context)
+    }
+
+    /// Sends requests to `base_url` rather than YouTube, e.g. to a mock server.
+    pub fn with_base_url(self, base_url: impl Into<String>) -> Self {
+        InnerTubeClient {
+            base_url: base_url.into(),
+            ..self
+        }
+    }
+
+    /// Requests a page from the `browse` endpoint, deserialized straight into `T`: a
+    /// [`Value`] for the parser, or any of the structs in [`crate::types`].
+    ///
+    /// The pipeline browses into a [`Value`] rather than a typed response on purpose: the
+    /// parser looks for the tab and its videos wherever they are, skips the renderers it has no
+    /// use for, and reports a structure change with the JSON path it happened at, which a
+    /// response failing to deserialize as a whole couldn't tell. The videos it finds are then
+    /// deserialized into [`crate::types::VideoRenderer`] and the like.
+    #[tracing::instrument(skip(self))]
+    pub async fn browse<T: DeserializeOwned>(&self, request: &BrowseRequest) -> Result<T, Error> {
+        let mut body = json!({ "context": { "client": self.context } });
+        match request {
+            BrowseRequest::Endpoint(BrowseEndpoint { browse_id, params }) => {
+                body["browseId"] = json!(browse_id);
+                if let Some(params) = params {
+                    body["params"] = json!(params);
+                }
+            }
+            BrowseRequest::Continuation(token) => body["continuation"] = json!(token),
+        }
+
+        self.post("browse", &body).await
+    }
+
+    /// Resolves the URL of a channel tab or playlist, e.g.
+    /// `https://www.youtube.com/@ParliamentofKenyaChannel/streams`, to the endpoint browsing it.
+    #[tracing::instrument(skip(self))]
+    pub async fn resolve_url(&self, url: &str) -> Result<BrowseEndpoint, Error> {
+        let body = json!({ "context": { "client": self.context }, "url": url });
+        let response = self
+            .post::<ResolveUrlResponse>("navigation/resolve_url", &body)
+            .await?;
+
+        response
+            .endpoint
+            .browse_endpoint
+            .ok_or_else(|| Error::StructureChanged {
+                path: "resolve_url.endpoint.browseEndpoint".to_string(),
+                reason: format!("{url} didn't resolve to a channel or playlist"),
+            })
+    }
+
+    async fn post<T: DeserializeOwned>(&self, endpoint: &str, body: &Value) -> Result<T, Error> {
+        let response = self
+            .http
+            .post(format!(
+                "{}/youtubei/v1/{endpoint}?prettyPrint=false",
+                self.base_url
+            ))
+            .header("Accept-Language", &self.context.hl)
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(serde_json::from_slice(&response)?)
+    }
+}
+
+/// The web client version `page_url` is served with, see [`extract_innertube_client_version`].
+async fn fetch_client_version(
+    http: &reqwest::Client,
+    page_url: &str,
+    hl: &str,
+) -> Result<String, Error> {
+    let document = http
+        .get(page_url)
+        .header("Accept-Language", hl)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    extract_innertube_client_version(&document).ok_or(Error::ParseError(
+        "No INNERTUBE_CLIENT_VERSION in the page's ytcfg",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        extract::State,
+        response::Html,
+        routing::{get, post},
+        Json, Router,
+    };
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::parser::{
+        parse_continuation_page, parse_streams_page, tests::video_item, ChannelTab,
+    };
+
+    type Requests = Arc<Mutex<Vec<Value>>>;
+
+    /// Serves canned InnerTube responses on a local port, recording the requests they answer.
+    async fn mock_innertube() -> (String, Requests) {
+        async fn resolve_url(
+            State(requests): State<Requests>,
+            Json(body): Json<Value>,
+        ) -> Json<Value> {
+            let endpoint = match body["url"].as_str() {
+                Some(url) if url.ends_with("/streams") => json!({
+                    "browseEndpoint": { "browseId": "UCparliament", "params": "EgdzdHJlYW1z" }
+                }),
+                _ => json!({ "watchEndpoint": { "videoId": "abc" } }),
+            };
+            requests.lock().unwrap().push(body);
+            Json(json!({ "endpoint": endpoint }))
+        }
+
+        async fn browse(State(requests): State<Requests>, Json(body): Json<Value>) -> Json<Value> {
+            let response = if body.get("continuation").is_some() {
+                json!({
+                    "onResponseReceivedActions": [{
+                        "appendContinuationItemsAction": { "continuationItems": [video_item("older")] }
+                    }]
+                })
+            } else {
+                json!({
+                    "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [{
+                        "tabRenderer": {
+                            "title": "Live",
+                            "endpoint": { "commandMetadata": { "webCommandMetadata": {
+                                "url": "/@ParliamentofKenyaChannel/streams"
+                            } } },
+                            "content": { "richGridRenderer": { "contents": [
+                                video_item("newest"),
+                                { "continuationItemRenderer": { "continuationEndpoint": {
+                                    "continuationCommand": { "token": "4qmFsgKrCBIYVUN" }
+                                } } }
+                            ] } }
+                        }
+                    }] } }
+                })
+            };
+            requests.lock().unwrap().push(body);
+            Json(response)
+        }
+
+        async fn channel_page() -> Html<&'static str> {
+            Html(
+                r#"<script>ytcfg.set({"INNERTUBE_CLIENT_NAME":"WEB","INNERTUBE_CLIENT_VERSION":"2.20251015.01.00"});</script>"#,
+            )
+        }
+
+        let requests = Requests::default();
+        let app = Router::new()
+            .route("/@ParliamentofKenyaChannel/streams", get(channel_page))
+            .route("/youtubei/v1/navigation/resolve_url", post(resolve_url))
+            .route("/youtubei/v1/browse", post(browse))
+            .with_state(requests.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (base_url, requests)
+    }
+
+    #[tokio::test]
+    async fn browses_channel_tabs_page_by_page() {
+        let (base_url, requests) = mock_innertube().await;
+        let client = InnerTubeClient::new(reqwest::Client::new(), ClientContext::default())
+            .with_base_url(base_url);
+
+        let endpoint = client
+            .resolve_url("https://www.youtube.com/@ParliamentofKenyaChannel/streams")
+            .await
+            .unwrap();
+        assert_eq!(
+            endpoint,
+            BrowseEndpoint {
+                browse_id: "UCparliament".to_string(),
+                params: Some("EgdzdHJlYW1z".to_string()),
+            }
+        );
+
+        let first_page = client
+            .browse::<Value>(&BrowseRequest::Endpoint(endpoint))
+            .await
+            .unwrap();
+        let page = parse_streams_page(&first_page, ChannelTab::Streams).unwrap();
+        assert_eq!(page.streams[0].video_id, "newest");
+        assert_eq!(page.streams[0].streamed_date, "Streamed 2 days ago");
+
+        let next_page = client
+            .browse::<Value>(&BrowseRequest::Continuation(page.continuation.unwrap()))
+            .await
+            .unwrap();
+        let page = parse_continuation_page(&next_page).unwrap();
+        assert_eq!(page.streams[0].video_id, "older");
+        assert_eq!(page.continuation, None);
+
+        let requests = requests.lock().unwrap();
+        let client_context = json!({
+            "clientName": "WEB",
+            "clientVersion": DEFAULT_CLIENT_VERSION,
+            "hl": "en",
+            "gl": "KE"
+        });
+        assert!(requests
+            .iter()
+            .all(|request| request["context"]["client"] == client_context));
+        assert_eq!(requests[1]["browseId"], "UCparliament");
+        assert_eq!(requests[1]["params"], "EgdzdHJlYW1z");
+        assert_eq!(requests[2]["continuation"], "4qmFsgKrCBIYVUN");
+    }
+
+    #[tokio::test]
+    async fn discovers_client_version_from_pages() {
+        let (base_url, requests) = mock_innertube().await;
+
+        let client = InnerTubeClient::discover(
+            reqwest::Client::new(),
+            &format!("{base_url}/@ParliamentofKenyaChannel/streams"),
+        )
+        .await
+        .with_base_url(base_url.clone());
+        client
+            .browse::<Value>(&BrowseRequest::Continuation("token".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            requests.lock().unwrap()[0]["context"]["client"]["clientVersion"],
+            "2.20251015.01.00"
+        );
+
+        // a page that can't be fetched leaves the last known version
+        let client =
+            InnerTubeClient::discover(reqwest::Client::new(), &format!("{base_url}/missing")).await;
+        assert_eq!(client.context.client_version, DEFAULT_CLIENT_VERSION);
+    }
+
+    #[tokio::test]
+    async fn reports_unresolvable_urls_and_failed_requests() {
+        let (base_url, _) = mock_innertube().await;
+        let client = InnerTubeClient::new(reqwest::Client::new(), ClientContext::default())
+            .with_base_url(base_url.clone());
+
+        assert!(matches!(
+            client
+                .resolve_url("https://www.youtube.com/watch?v=abc")
+                .await,
+            Err(Error::StructureChanged { path, .. }) if path == "resolve_url.endpoint.browseEndpoint"
+        ));
+
+        let client = client.with_base_url(format!("{base_url}/missing"));
+        assert!(matches!(
+            client
+                .browse::<Value>(&BrowseRequest::Continuation("token".to_string()))
+                .await,
+            Err(Error::RequestError(e)) if e.status() == Some(reqwest::StatusCode::NOT_FOUND)
+        ));
+    }
+}
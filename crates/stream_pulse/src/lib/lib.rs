@@ -1,11 +1,21 @@
 mod app;
 mod error;
+pub mod fixtures;
+mod innertube;
 mod parser;
 mod process_stream;
+mod quotes;
+mod sources;
 pub mod summary;
 pub mod tracing;
 pub mod types;
 
 pub use app::{cron::start_cron, server::start_server, AppState};
-use parser::{extract_json_from_script, parse_streams};
-pub use process_stream::fetch_and_process_streams;
+pub use innertube::{BrowseRequest, InnerTubeClient};
+use parser::extract_json_from_script;
+pub use parser::{ChannelTab, StreamsPage};
+pub use process_stream::{
+    backfill_streams, fetch_and_process_streams, process_ended_streams, transcribe_stream_section,
+    ytdlp_version,
+};
+pub use sources::{Source, SourceKind};
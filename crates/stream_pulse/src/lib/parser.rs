@@ -6,68 +6,371 @@
 //! ## Key Components
 //!
 //! - `Stream`: A struct representing a single YouTube stream.
-//! - `parse_streams`: A function to parse multiple streams from YouTube JSON data.
-//! - `extract_json_from_script`: A function to extract the `ytInitialData` JSON object from a YouTube page's HTML.
+//! - `parse_streams_page`: A function to parse the first page of streams from YouTube JSON data.
+//! - `parse_playlist_page`: Its counterpart for playlists.
+//! - `parse_continuation_page`: A function to parse later pages, requested from the InnerTube
+//!   browse endpoint with the continuation token of the page before.
+//! - `extract_json_from_script`: A function to extract the `ytInitialData` JSON object from a YouTube page's HTML,
+//!   which the pipeline now requests from InnerTube instead (see `innertube`) but the page fixtures are kept as.
 
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_json::Value;
-use stream_datastore::Stream;
+use stream_datastore::{LiveStatus, ScheduledStream, Stream};
+
+use crate::{
+    error::Error,
+    types::{PlaylistVideoRenderer, TextRuns, Thumbnail, VideoRenderer},
+};
+
+/// One page of a channel tab's streams, with the token to request the next one
+#[derive(Debug, Default)]
+pub struct StreamsPage {
+    /// Archived streams
+    pub streams: Vec<Stream>,
+    /// Upcoming and live streams
+    pub scheduled: Vec<ScheduledStream>,
+    /// Continuation token for the InnerTube browse endpoint, `None` on the last page
+    pub continuation: Option<String>,
+}
+
+/// Channel tabs listing videos in a `richGridRenderer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelTab {
+    /// Past live streams, titled "Live" on most channels
+    Streams,
+    /// Uploaded videos
+    Videos,
+}
+
+impl ChannelTab {
+    /// Last segment of the tab's URL, e.g. `/@ParliamentofKenyaChannel/streams`
+    fn url_suffix(&self) -> &'static str {
+        match self {
+            ChannelTab::Streams => "/streams",
+            ChannelTab::Videos => "/videos",
+        }
+    }
+
+    fn titles(&self) -> &'static [&'static str] {
+        match self {
+            ChannelTab::Streams => &["Live", "Streams"],
+            ChannelTab::Videos => &["Videos"],
+        }
+    }
+
+    /// Whether a `tabRenderer` is this tab, going by its endpoint URL or, failing that, its title
+    fn matches(&self, tab_renderer: &Value) -> bool {
+        let url = tab_renderer["endpoint"]["commandMetadata"]["webCommandMetadata"]["url"].as_str();
+        let title = tab_renderer["title"].as_str();
+
+        url.is_some_and(|url| url.ends_with(self.url_suffix()))
+            || title.is_some_and(|title| self.titles().contains(&title))
+    }
+}
+
+/// Appends `keys` to the JSON path `root`, numeric keys being array indices
+fn json_path(root: &str, keys: &[&str]) -> String {
+    keys.iter()
+        .fold(root.to_string(), |path, key| match key.parse::<usize>() {
+            Ok(i) => format!("{path}[{i}]"),
+            Err(_) => format!("{path}.{key}"),
+        })
+}
+
+/// Looks up `keys` (object keys, or indices into arrays) under `value`, reporting the JSON path
+/// from `root` that's missing.
+fn lookup<'a>(value: &'a Value, root: &str, keys: &[&str]) -> Result<&'a Value, Error> {
+    keys.iter()
+        .enumerate()
+        .try_fold(value, |current, (depth, key)| {
+            match key.parse::<usize>() {
+                Ok(i) => current.get(i),
+                Err(_) => current.get(key),
+            }
+            .ok_or_else(|| Error::StructureChanged {
+                path: json_path(root, &keys[..=depth]),
+                reason: "missing".to_string(),
+            })
+        })
+}
+
+/// Like [`lookup`], for values expected to be arrays.
+fn lookup_array<'a>(value: &'a Value, root: &str, keys: &[&str]) -> Result<&'a [Value], Error> {
+    lookup(value, root, keys)?
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or_else(|| Error::StructureChanged {
+            path: json_path(root, keys),
+            reason: "expected an array".to_string(),
+        })
+}
+
+/// Finds a channel tab in `ytInitialData` by its URL or title, returning its JSON path too.
+pub fn find_tab(json: &Value, tab: ChannelTab) -> Result<(String, &Value), Error> {
+    const TABS_PATH: &str = "ytInitialData.contents.twoColumnBrowseResultsRenderer.tabs";
+    let tabs = lookup_array(
+        json,
+        "ytInitialData",
+        &["contents", "twoColumnBrowseResultsRenderer", "tabs"],
+    )?;
 
-use crate::{error::Error, types::VideoRenderer};
+    tabs.iter()
+        .enumerate()
+        .find(|(_, item)| tab.matches(&item["tabRenderer"]))
+        .map(|(i, item)| (format!("{TABS_PATH}[{i}]"), item))
+        .ok_or_else(|| Error::StructureChanged {
+            path: TABS_PATH.to_string(),
+            reason: format!(
+                "no {tab:?} tab among {:?}",
+                tabs.iter()
+                    .filter_map(|item| item["tabRenderer"]["title"].as_str())
+                    .collect::<Vec<_>>()
+            ),
+        })
+}
 
-/// Parses multiple streams from the provided JSON data.
+/// Parses the first page of streams from the `ytInitialData` of a channel tab, along with the
+/// token to request the next page.
 ///
 /// # Parameters
 /// * `json`: A reference to a `Value` containing the YouTube page's JSON data.
+/// * `tab`: The tab the page was requested for, its content is only included for that one.
 ///
 /// # Returns
-/// * `Ok(Vec<Stream>)` containing all successfully parsed streams.
-/// * `Err(YtScrapeError)` if the JSON structure is unexpected or parsing fails.
+/// * `Ok(StreamsPage)` containing all successfully parsed streams.
+/// * `Err(Error::StructureChanged)` with the failing JSON path if the structure is unexpected.
 #[tracing::instrument(skip(json))]
-pub fn parse_streams(json: &Value) -> Result<Vec<Stream>, Error> {
-    let mut streams = Vec::new();
-
-    if let Some(contents) = json["contents"]["twoColumnBrowseResultsRenderer"]["tabs"]
-        .get(2)
-        .ok_or(Error::ParseError("Failed to get item at idx 2 from ytInitialData['contents']['twoColumnBrowseResultsRenderer']['tabs']"))
-        .map(|tab| tab["tabRenderer"]["content"]["richGridRenderer"]["contents"].as_array())?
-    {
-        for item in contents {
-            if let Ok(video_renderer) =
-                item["richItemRenderer"]["content"]["videoRenderer"].as_object()
-                .ok_or(Error::ParseError("Failed to get item['richItemRenderer']['content']['videoRenderer']"))
-            {
-                let video_renderer =
-                    serde_json::from_value::<VideoRenderer>(Value::Object(video_renderer.clone()))?;
-                // Only process the video if it's not an upcoming / live event
-                if video_renderer.upcoming_event_data.is_some() || video_renderer.view_count_text.is_none() || video_renderer.published_time_text.is_none() {
-                    continue;
-                }
-                let StreamWrapper(stream) = StreamWrapper::try_from(video_renderer)?;
+pub fn parse_streams_page(json: &Value, tab: ChannelTab) -> Result<StreamsPage, Error> {
+    let (tab_path, tab) = find_tab(json, tab)?;
+    let contents = lookup_array(
+        tab,
+        &tab_path,
+        &["tabRenderer", "content", "richGridRenderer", "contents"],
+    )?;
 
-                //XXX: Skip if duration is < 10 minutes
-                if let Some(duration_secs) = parse_duration_to_seconds(&stream.duration) {
-                    if duration_secs < 600 {
-                        continue;
-                    }
-                } else {
-                    // XXX: skip if duration could not be parsed
-                    continue;
-                }
+    parse_items(
+        contents,
+        &format!("{tab_path}.tabRenderer.content.richGridRenderer.contents"),
+    )
+}
+
+/// Parses the first page of streams from the `ytInitialData` of a playlist, along with the
+/// token to request the next page.
+#[tracing::instrument(skip(json))]
+pub fn parse_playlist_page(json: &Value) -> Result<StreamsPage, Error> {
+    const KEYS: &[&str] = &[
+        "contents",
+        "twoColumnBrowseResultsRenderer",
+        "tabs",
+        "0",
+        "tabRenderer",
+        "content",
+        "sectionListRenderer",
+        "contents",
+        "0",
+        "itemSectionRenderer",
+        "contents",
+        "0",
+        "playlistVideoListRenderer",
+        "contents",
+    ];
+    let contents = lookup_array(json, "ytInitialData", KEYS)?;
+
+    parse_items(contents, &json_path("ytInitialData", KEYS))
+}
+
+/// Parses a page of streams from an InnerTube browse response to a continuation request.
+#[tracing::instrument(skip(json))]
+pub fn parse_continuation_page(json: &Value) -> Result<StreamsPage, Error> {
+    let actions = lookup_array(json, "browse", &["onResponseReceivedActions"])?;
+    let mut page = StreamsPage::default();
+
+    for (i, action) in actions.iter().enumerate() {
+        let Some((kind, items)) = [
+            "appendContinuationItemsAction",
+            "reloadContinuationItemsCommand",
+        ]
+        .into_iter()
+        .find_map(|kind| Some((kind, action[kind]["continuationItems"].as_array()?))) else {
+            continue;
+        };
+
+        let items_page = parse_items(
+            items,
+            &format!("browse.onResponseReceivedActions[{i}].{kind}.continuationItems"),
+        )?;
+        page.streams.extend(items_page.streams);
+        page.scheduled.extend(items_page.scheduled);
+        page.continuation = items_page.continuation.or(page.continuation);
+    }
+
+    Ok(page)
+}
+
+/// Parses the videos of a channel tab's `richGridRenderer` or of a `playlistVideoListRenderer`,
+/// setting upcoming and live streams apart and skipping short ones, and picks up the trailing
+/// `continuationItemRenderer` token if any.
+fn parse_items(items: &[Value], path: &str) -> Result<StreamsPage, Error> {
+    let mut page = StreamsPage::default();
 
-                streams.push(stream);
+    for (i, item) in items.iter().enumerate() {
+        if let Some(token) = item["continuationItemRenderer"]["continuationEndpoint"]
+            ["continuationCommand"]["token"]
+            .as_str()
+        {
+            page.continuation = Some(token.to_string());
+            continue;
+        }
+
+        let listing =
+            if let Some(video_renderer) = item.pointer("/richItemRenderer/content/videoRenderer") {
+                parse_item::<VideoRenderer>(
+                    video_renderer,
+                    &format!("{path}[{i}].richItemRenderer.content.videoRenderer"),
+                )?
+            } else if let Some(playlist_video_renderer) = item.get("playlistVideoRenderer") {
+                parse_item::<PlaylistVideoRenderer>(
+                    playlist_video_renderer,
+                    &format!("{path}[{i}].playlistVideoRenderer"),
+                )?
+            } else {
+                continue;
+            };
+        let stream = match listing {
+            Some(Listing::Archived(stream)) => *stream,
+            None => continue,
+            Some(Listing::Scheduled(scheduled)) => {
+                page.scheduled.push(scheduled);
+                continue;
+            }
+        };
+
+        //XXX: Skip if duration is < 10 minutes
+        if let Some(duration_secs) = parse_duration_to_seconds(&stream.duration) {
+            if duration_secs < 600 {
+                continue;
             }
+        } else {
+            // XXX: skip if duration could not be parsed
+            continue;
         }
-    } else {
-        return Err(Error::ParseError(
-            "Failed to get script contents, structure might have changed",
-        ));
+
+        page.streams.push(stream);
+    }
+
+    Ok(page)
+}
+
+/// A video as listed on a channel tab or playlist
+enum Listing {
+    Archived(Box<Stream>),
+    Scheduled(ScheduledStream),
+}
+
+/// Deserializes a video renderer found at `path`.
+///
+/// A renderer that no longer deserializes means the listing's structure changed, while one that
+/// only lacks a field of its own (e.g. a premiere without a publish date) is skipped with a
+/// warning so that it doesn't hide the rest of the page.
+fn parse_item<R>(renderer: &Value, path: &str) -> Result<Option<Listing>, Error>
+where
+    R: for<'a> Deserialize<'a> + Schedulable,
+    StreamWrapper: TryFrom<R, Error = Error>,
+{
+    let structure_changed = |reason: String| Error::StructureChanged {
+        path: path.to_string(),
+        reason,
+    };
+    let renderer = R::deserialize(renderer).map_err(|e| structure_changed(e.to_string()))?;
+    // Upcoming / live events are only processed once archived
+    if let Some(scheduled) = renderer.scheduled() {
+        return Ok(Some(Listing::Scheduled(scheduled)));
+    }
+
+    match StreamWrapper::try_from(renderer) {
+        Ok(StreamWrapper(stream)) => Ok(Some(Listing::Archived(Box::new(stream)))),
+        Err(e) => {
+            tracing::warn!(path, error = %e, "Skipping video that could not be parsed");
+            Ok(None)
+        }
+    }
+}
+
+/// Tells upcoming and ongoing live streams apart from archived videos
+trait Schedulable {
+    /// The stream's schedule, `None` once it's archived
+    fn scheduled(&self) -> Option<ScheduledStream>;
+}
+
+impl Schedulable for VideoRenderer {
+    fn scheduled(&self) -> Option<ScheduledStream> {
+        let (status, scheduled_start) = match &self.upcoming_event_data {
+            Some(upcoming) => (LiveStatus::Upcoming, parse_start_time(&upcoming.start_time)),
+            // live streams show how many are watching instead of a publish date
+            None if self.published_time_text.is_none() => (LiveStatus::Live, None),
+            None => return None,
+        };
+
+        Some(ScheduledStream {
+            video_id: self.video_id.clone(),
+            title: first_run_text(&self.title),
+            status,
+            scheduled_start,
+            source: None,
+        })
     }
+}
+
+impl Schedulable for PlaylistVideoRenderer {
+    fn scheduled(&self) -> Option<ScheduledStream> {
+        let (status, scheduled_start) = match &self.upcoming_event_data {
+            Some(upcoming) => (LiveStatus::Upcoming, parse_start_time(&upcoming.start_time)),
+            // lengths are only known once a stream has ended
+            None if self.length_text.is_none() => (LiveStatus::Live, None),
+            None => return None,
+        };
+
+        Some(ScheduledStream {
+            video_id: self.video_id.clone(),
+            title: first_run_text(&self.title),
+            status,
+            scheduled_start,
+            source: None,
+        })
+    }
+}
 
-    Ok(streams)
+/// Parses an `upcomingEventData.startTime`, seconds since the epoch, e.g. `"1750170600"`
+fn parse_start_time(start_time: &str) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(start_time.parse().ok()?, 0)
 }
 
-fn parse_duration_to_seconds(duration_str: &str) -> Option<u64> {
+fn first_run_text(text: &TextRuns) -> String {
+    text.runs
+        .first()
+        .map(|run| run.text.clone())
+        .unwrap_or_default()
+}
+
+/// Extracts the web client version YouTube served the page with, e.g. `2.20250620.01.00`,
+/// which browse requests are expected to identify with.
+pub fn extract_innertube_client_version(document: &str) -> Option<String> {
+    static RE_CLIENT_VERSION: LazyLock<regex::Regex> = LazyLock::new(|| {
+        regex::Regex::new(r#""INNERTUBE_CLIENT_VERSION"\s*:\s*"([^"]+)""#).unwrap()
+    });
+
+    RE_CLIENT_VERSION
+        .captures(document)
+        .and_then(|cap| cap.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Parses a YouTube `lengthText` duration such as `"3:45:12"` into seconds
+pub(crate) fn parse_duration_to_seconds(duration_str: &str) -> Option<u64> {
     let parts: Vec<u64> = duration_str
         .split(':')
         .filter_map(|p| p.parse::<u64>().ok())
@@ -98,10 +401,13 @@ impl TryFrom<VideoRenderer> for StreamWrapper {
     fn try_from(
         VideoRenderer {
             video_id,
+            thumbnail,
             title,
             published_time_text,
             view_count_text,
             length_text,
+            description_snippet,
+            badges,
             ..
         }: VideoRenderer,
     ) -> Result<Self, Self::Error> {
@@ -124,16 +430,25 @@ impl TryFrom<VideoRenderer> for StreamWrapper {
             .simple_text
             .ok_or(Error::ParseError("No value found for 'simpleText'"))
             .unwrap_or_default();
-        let duration = length_text
-            .ok_or(Error::ParseError("No value found for 'lengthText'"))?
-            .simple_text;
+        let length_text =
+            length_text.ok_or(Error::ParseError("No value found for 'lengthText'"))?;
+        let badges = badges
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|badge| badge.metadata_badge_renderer?.label)
+            .collect();
 
         let stream = Stream {
             video_id,
             title: title.to_string(),
             view_count,
             streamed_date,
-            duration,
+            duration: length_text.simple_text,
+            thumbnail_url: best_thumbnail_url(&thumbnail),
+            description_snippet: description_snippet
+                .map(|snippet| snippet.runs.into_iter().map(|run| run.text).collect()),
+            badges,
+            duration_label: Some(length_text.accessibility.accessibility_data.label),
             ..Default::default()
         };
 
@@ -141,6 +456,65 @@ impl TryFrom<VideoRenderer> for StreamWrapper {
     }
 }
 
+impl TryFrom<PlaylistVideoRenderer> for StreamWrapper {
+    type Error = Error;
+
+    /// Attempts to create a `Stream` from a playlistVideoRenderer object, whose view count and
+    /// date share the `videoInfo` runs, e.g. `["1.2K views", " • ", "Streamed 3 days ago"]`.
+    fn try_from(
+        PlaylistVideoRenderer {
+            video_id,
+            thumbnail,
+            title,
+            length_text,
+            video_info,
+            ..
+        }: PlaylistVideoRenderer,
+    ) -> Result<Self, Self::Error> {
+        let title = title
+            .runs
+            .into_iter()
+            .next()
+            .ok_or(Error::ParseError(
+                "Failed to get video title via ['title']['runs'][0]['text']",
+            ))?
+            .text;
+        let mut video_info = video_info.map(|info| info.runs).unwrap_or_default();
+        let streamed_date = if video_info.len() > 1 {
+            video_info.pop().map(|run| run.text).unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let view_count = video_info
+            .into_iter()
+            .next()
+            .map(|run| run.text)
+            .unwrap_or_default();
+        let length_text =
+            length_text.ok_or(Error::ParseError("No value found for 'lengthText'"))?;
+
+        Ok(StreamWrapper(Stream {
+            video_id,
+            title,
+            view_count,
+            streamed_date,
+            duration: length_text.simple_text,
+            thumbnail_url: thumbnail.as_ref().and_then(best_thumbnail_url),
+            duration_label: Some(length_text.accessibility.accessibility_data.label),
+            ..Default::default()
+        }))
+    }
+}
+
+/// The URL of the largest of a video's thumbnails
+fn best_thumbnail_url(thumbnail: &Thumbnail) -> Option<String> {
+    thumbnail
+        .thumbnails
+        .iter()
+        .max_by_key(|item| item.width * item.height)
+        .map(|item| item.url.clone())
+}
+
 /// Extracts the `ytInitialData` JSON object from a YouTube page's HTML script.
 ///
 /// # Context
@@ -164,22 +538,22 @@ impl TryFrom<VideoRenderer> for StreamWrapper {
 /// This method is somewhat fragile as it depends on the specific structure of YouTube's
 /// HTML. If YouTube changes how they embed this data, this function may need to be updated.
 pub fn extract_json_from_script<T: for<'a> Deserialize<'a>>(document: &str) -> Result<T, Error> {
-    let re =
+    static RE_INITIAL_DATA: LazyLock<regex::Regex> = LazyLock::new(|| {
         regex::Regex::new(r"(?s)<script[^>]*>\s*var\s+ytInitialData\s*=\s*(\{.*?\});\s*</script>")
-            .unwrap();
-    let result = re
+            .unwrap()
+    });
+
+    RE_INITIAL_DATA
         .captures(document)
         .and_then(|cap| cap.get(1))
         .and_then(|m| serde_json::from_str(m.as_str()).ok())
         .ok_or(Error::ParseError(
             "Failed to extract ytInitialData from the page's script tag",
-        ));
-
-    result
+        ))
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use serde_json::json;
 
@@ -265,6 +639,367 @@ mod tests {
         assert!(matches!(result, Err(Error::ParseError(_))));
     }
 
+    /// A channel tab's video, archived and long enough to be processed
+    pub(crate) fn video_item(video_id: &str) -> Value {
+        json!({
+            "richItemRenderer": {
+                "content": {
+                    "videoRenderer": {
+                        "videoId": video_id,
+                        "thumbnail": { "thumbnails": [] },
+                        "title": { "runs": [{ "text": "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025" }] },
+                        "publishedTimeText": { "simpleText": "Streamed 2 days ago" },
+                        "viewCountText": { "simpleText": "1,234 views" },
+                        "lengthText": {
+                            "accessibility": { "accessibilityData": { "label": "3 hours" } },
+                            "simpleText": "3:00:00"
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn playlist_video_item(video_id: &str, length: Option<&str>) -> Value {
+        let mut item = json!({
+            "playlistVideoRenderer": {
+                "videoId": video_id,
+                "thumbnail": { "thumbnails": [
+                    { "url": format!("https://i.ytimg.com/vi/{video_id}/hqdefault.jpg"), "width": 336, "height": 188 }
+                ] },
+                "title": { "runs": [{ "text": "SENATE | STANDING COMMITTEE ON FINANCE AND BUDGET" }] },
+                "videoInfo": { "runs": [
+                    { "text": "812 views" }, { "text": " • " }, { "text": "Streamed 3 days ago" }
+                ] }
+            }
+        });
+        if let Some(length) = length {
+            item["playlistVideoRenderer"]["lengthText"] = json!({
+                "accessibility": { "accessibilityData": { "label": "2 hours" } },
+                "simpleText": length
+            });
+        }
+        item
+    }
+
+    #[test]
+    fn parses_playlist_pages() {
+        let json = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                { "tabRenderer": { "content": { "sectionListRenderer": { "contents": [
+                    { "itemSectionRenderer": { "contents": [
+                        { "playlistVideoListRenderer": { "contents": [
+                            // still live
+                            playlist_video_item("live", None),
+                            playlist_video_item("archived", Some("2:01:30")),
+                            continuation_item("4qmFsgKrCBIYVUN")
+                        ] } }
+                    ] } }
+                ] } } } }
+            ] } }
+        });
+
+        let page = parse_playlist_page(&json).unwrap();
+
+        assert_eq!(page.continuation.as_deref(), Some("4qmFsgKrCBIYVUN"));
+        assert_eq!(page.streams.len(), 1);
+        let stream = &page.streams[0];
+        assert_eq!(stream.video_id, "archived");
+        assert_eq!(stream.view_count, "812 views");
+        assert_eq!(stream.streamed_date, "Streamed 3 days ago");
+        assert_eq!(stream.duration, "2:01:30");
+        assert_eq!(stream.duration_label.as_deref(), Some("2 hours"));
+        assert_eq!(
+            stream.thumbnail_url.as_deref(),
+            Some("https://i.ytimg.com/vi/archived/hqdefault.jpg")
+        );
+        assert_eq!(page.scheduled.len(), 1);
+        assert_eq!(page.scheduled[0].video_id, "live");
+        assert_eq!(page.scheduled[0].status, LiveStatus::Live);
+
+        // a channel page isn't a playlist
+        match parse_playlist_page(
+            &json!({ "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [] } } }),
+        ) {
+            Err(Error::StructureChanged { path, .. }) => assert_eq!(
+                path,
+                "ytInitialData.contents.twoColumnBrowseResultsRenderer.tabs[0]"
+            ),
+            other => panic!("Expected StructureChanged, got {other:?}"),
+        }
+    }
+
+    fn tab(title: &str, url: &str, contents: Option<Value>) -> Value {
+        let mut tab = json!({
+            "tabRenderer": {
+                "title": title,
+                "endpoint": { "commandMetadata": { "webCommandMetadata": { "url": url } } }
+            }
+        });
+        if let Some(contents) = contents {
+            tab["tabRenderer"]["content"] = json!({ "richGridRenderer": { "contents": contents } });
+        }
+        tab
+    }
+
+    #[test]
+    fn finds_tabs_wherever_they_are() {
+        // a Shorts tab shifts the streams tab along
+        let json = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Home", "/@ParliamentofKenyaChannel/featured", None),
+                tab("Videos", "/@ParliamentofKenyaChannel/videos", Some(json!([video_item("upload")]))),
+                tab("Shorts", "/@ParliamentofKenyaChannel/shorts", None),
+                tab("Live", "/@ParliamentofKenyaChannel/streams", None),
+            ] } }
+        });
+
+        let (path, _) = find_tab(&json, ChannelTab::Streams).unwrap();
+        assert_eq!(
+            path,
+            "ytInitialData.contents.twoColumnBrowseResultsRenderer.tabs[3]"
+        );
+
+        let page = parse_streams_page(&json, ChannelTab::Videos).unwrap();
+        assert_eq!(page.streams[0].video_id, "upload");
+
+        // the streams tab's content is only included when it's the one requested
+        match parse_streams_page(&json, ChannelTab::Streams) {
+            Err(Error::StructureChanged { path, .. }) => assert_eq!(
+                path,
+                "ytInitialData.contents.twoColumnBrowseResultsRenderer.tabs[3].tabRenderer.content"
+            ),
+            other => panic!("Expected StructureChanged, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_missing_tabs_and_malformed_videos() {
+        let no_streams_tab = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Home", "/@ParliamentofKenyaChannel/featured", None),
+            ] } }
+        });
+        assert!(matches!(
+            parse_streams_page(&no_streams_tab, ChannelTab::Streams),
+            Err(Error::StructureChanged { reason, .. }) if reason.contains("Home")
+        ));
+
+        let malformed = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Live", "/@ParliamentofKenyaChannel/streams", Some(json!([
+                    { "richItemRenderer": { "content": { "videoRenderer": { "videoId": "abc" } } } }
+                ])))
+            ] } }
+        });
+        assert!(matches!(
+            parse_streams_page(&malformed, ChannelTab::Streams),
+            Err(Error::StructureChanged { path, .. })
+                if path.ends_with("tabs[0].tabRenderer.content.richGridRenderer.contents[0].richItemRenderer.content.videoRenderer")
+        ));
+
+        // a single video lacking a field of its own is skipped rather than failing the page
+        let mut undated = video_item("undated");
+        undated["richItemRenderer"]["content"]["videoRenderer"]
+            .as_object_mut()
+            .unwrap()
+            .remove("publishedTimeText");
+        let partly_malformed = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Live", "/@ParliamentofKenyaChannel/streams", Some(json!([
+                    undated,
+                    video_item("dated")
+                ])))
+            ] } }
+        });
+        let page = parse_streams_page(&partly_malformed, ChannelTab::Streams).unwrap();
+        assert_eq!(page.streams.len(), 1);
+        assert_eq!(page.streams[0].video_id, "dated");
+
+        // an empty tab is not a structure change
+        let empty = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Live", "/@ParliamentofKenyaChannel/streams", Some(json!([])))
+            ] } }
+        });
+        assert!(parse_streams_page(&empty, ChannelTab::Streams)
+            .unwrap()
+            .streams
+            .is_empty());
+    }
+
+    fn continuation_item(token: &str) -> Value {
+        json!({
+            "continuationItemRenderer": {
+                "continuationEndpoint": { "continuationCommand": { "token": token } }
+            }
+        })
+    }
+
+    #[test]
+    fn parses_first_page_with_continuation() {
+        let json = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Home", "/@ParliamentofKenyaChannel/featured", None),
+                tab("Videos", "/@ParliamentofKenyaChannel/videos", None),
+                tab("Live", "/@ParliamentofKenyaChannel/streams", Some(json!([
+                    video_item("first"),
+                    video_item("second"),
+                    continuation_item("4qmFsgKrCBIYVUN")
+                ])))
+            ] } }
+        });
+
+        let page = parse_streams_page(&json, ChannelTab::Streams).unwrap();
+
+        assert_eq!(
+            page.streams
+                .iter()
+                .map(|s| s.video_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+        assert_eq!(page.continuation.as_deref(), Some("4qmFsgKrCBIYVUN"));
+    }
+
+    #[test]
+    fn keeps_what_videos_are_previewed_with() {
+        let mut item = video_item("captioned");
+        let renderer = &mut item["richItemRenderer"]["content"]["videoRenderer"];
+        renderer["thumbnail"] = json!({ "thumbnails": [
+            { "url": "https://i.ytimg.com/vi/captioned/hqdefault.jpg?sqp=small", "width": 168, "height": 94 },
+            { "url": "https://i.ytimg.com/vi/captioned/hqdefault.jpg?sqp=large", "width": 336, "height": 188 },
+            { "url": "https://i.ytimg.com/vi/captioned/hqdefault.jpg?sqp=medium", "width": 246, "height": 138 }
+        ] });
+        renderer["descriptionSnippet"] = json!({ "runs": [
+            { "text": "Live proceedings of the " }, { "text": "National Assembly" }
+        ] });
+        renderer["badges"] = json!([
+            { "metadataBadgeRenderer": { "style": "BADGE_STYLE_TYPE_SIMPLE", "label": "CC" } },
+            { "metadataBadgeRenderer": { "style": "BADGE_STYLE_TYPE_SIMPLE" } }
+        ]);
+
+        let json = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Live", "/@ParliamentofKenyaChannel/streams", Some(json!([
+                    item, video_item("plain")
+                ])))
+            ] } }
+        });
+
+        let page = parse_streams_page(&json, ChannelTab::Streams).unwrap();
+
+        let captioned = &page.streams[0];
+        assert_eq!(
+            captioned.thumbnail_url.as_deref(),
+            Some("https://i.ytimg.com/vi/captioned/hqdefault.jpg?sqp=large")
+        );
+        assert_eq!(
+            captioned.description_snippet.as_deref(),
+            Some("Live proceedings of the National Assembly")
+        );
+        assert_eq!(captioned.badges, vec!["CC"]);
+        assert!(captioned.has_captions());
+        assert_eq!(captioned.duration_label.as_deref(), Some("3 hours"));
+
+        let plain = &page.streams[1];
+        assert_eq!(plain.thumbnail_url, None);
+        assert_eq!(plain.description_snippet, None);
+        assert!(plain.badges.is_empty());
+        assert!(!plain.has_captions());
+    }
+
+    #[test]
+    fn sets_upcoming_and_live_streams_apart() {
+        let mut upcoming = video_item("upcoming");
+        let renderer = &mut upcoming["richItemRenderer"]["content"]["videoRenderer"];
+        renderer["upcomingEventData"] = json!({
+            "isReminderSet": false,
+            "startTime": "1750170600",
+            "upcomingEventText": { "runs": [{ "text": "Scheduled for " }, { "text": "DATE_PLACEHOLDER" }] }
+        });
+        renderer.as_object_mut().unwrap().remove("lengthText");
+        let mut live = video_item("live");
+        let renderer = &mut live["richItemRenderer"]["content"]["videoRenderer"];
+        renderer["viewCountText"] = json!({ "runs": [{ "text": "312" }, { "text": " watching" }] });
+        renderer
+            .as_object_mut()
+            .unwrap()
+            .remove("publishedTimeText");
+        renderer.as_object_mut().unwrap().remove("lengthText");
+
+        let json = json!({
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [
+                tab("Live", "/@ParliamentofKenyaChannel/streams", Some(json!([
+                    upcoming, live, video_item("archived")
+                ])))
+            ] } }
+        });
+
+        let page = parse_streams_page(&json, ChannelTab::Streams).unwrap();
+
+        assert_eq!(page.streams.len(), 1);
+        assert_eq!(
+            page.scheduled,
+            vec![
+                ScheduledStream {
+                    video_id: "upcoming".to_string(),
+                    title: "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025".to_string(),
+                    status: LiveStatus::Upcoming,
+                    scheduled_start: DateTime::from_timestamp(1_750_170_600, 0),
+                    source: None,
+                },
+                ScheduledStream {
+                    video_id: "live".to_string(),
+                    title: "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025".to_string(),
+                    status: LiveStatus::Live,
+                    scheduled_start: None,
+                    source: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_continuation_pages() {
+        let json = json!({
+            "onResponseReceivedActions": [{
+                "appendContinuationItemsAction": {
+                    "continuationItems": [video_item("older"), continuation_item("next")]
+                }
+            }]
+        });
+        let last_page = json!({
+            "onResponseReceivedActions": [{
+                "appendContinuationItemsAction": { "continuationItems": [video_item("oldest")] }
+            }]
+        });
+
+        let page = parse_continuation_page(&json).unwrap();
+        assert_eq!(page.streams[0].video_id, "older");
+        assert_eq!(page.continuation.as_deref(), Some("next"));
+
+        let page = parse_continuation_page(&last_page).unwrap();
+        assert_eq!(page.streams[0].video_id, "oldest");
+        assert!(page.continuation.is_none());
+
+        assert!(matches!(
+            parse_continuation_page(&json!({ "error": {} })),
+            Err(Error::StructureChanged { path, .. }) if path == "browse.onResponseReceivedActions"
+        ));
+    }
+
+    #[test]
+    fn extracts_client_version() {
+        let document = r#"<script>ytcfg.set({"INNERTUBE_CLIENT_NAME":"WEB","INNERTUBE_CLIENT_VERSION":"2.20250620.01.00"});</script>"#;
+
+        assert_eq!(
+            extract_innertube_client_version(document).as_deref(),
+            Some("2.20250620.01.00")
+        );
+        assert!(extract_innertube_client_version("<html></html>").is_none());
+    }
+
     #[test]
     fn test_extraction_with_invalid_json() {
         let html_content_invalid_json = r#"
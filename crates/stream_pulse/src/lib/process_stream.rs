@@ -1,5 +1,6 @@
 use another_tiktoken_rs::cl100k_base;
 use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use openai_dive::v1::{
     api::Client as OpenAiClient,
@@ -17,16 +18,32 @@ use openai_dive::v1::{
 };
 use rayon::prelude::*;
 use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
 use std::{
-    fs::{create_dir_all, remove_dir_all, OpenOptions},
+    collections::HashSet,
+    fs::{create_dir_all, remove_dir_all, remove_file, OpenOptions},
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, LazyLock},
+    time::Duration,
+};
+use stream_datastore::{DataStore, ScheduledStream, Stream};
+use ytdlp_bindings::{
+    AudioChunk, AudioProcessingConfig, AudioProcessor, MediaProber, SubtitleFormat, SubtitleWriter,
+    TimeMap, TimeRange, TranscriptSegment, YtDlp, YtDlpError,
 };
-use stream_datastore::{DataStore, Stream};
-use ytdlp_bindings::{AudioProcessor, YtDlp};
 
-use crate::{extract_json_from_script, parse_streams, summary::summarize_linear};
+use crate::{
+    error::Error,
+    innertube::{BrowseRequest, InnerTubeClient},
+    parser::{parse_continuation_page, parse_duration_to_seconds},
+    quotes::{
+        key_moments, locate_moment, locate_quote, notable_quotes, HighlightClip, HighlightKind,
+    },
+    sources::Source,
+    summary::summarize_linear,
+};
 
 static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
 static YTDLP: LazyLock<YtDlp> = LazyLock::new(|| {
@@ -37,11 +54,44 @@ static YTDLP: LazyLock<YtDlp> = LazyLock::new(|| {
 });
 static OPENAI: LazyLock<OpenAiClient> = LazyLock::new(openai_dive::v1::api::Client::new_from_env);
 
-//  Parliament of Kenya Channel Stream URL
-const YOUTUBE_STREAM_URL: &str = "https://www.youtube.com/@ParliamentofKenyaChannel/streams";
+// Upper bound on the pages of ~30 streams walked in a single run
+const MAX_STREAM_PAGES: usize = 100;
+// How far back regular runs look for streams, backfills set their own cutoff
+const DISCOVERY_WINDOW: chrono::Duration = chrono::Duration::days(14);
+// Upcoming and live streams no longer listed by any source are forgotten after this long
+const SCHEDULED_STREAM_TTL: chrono::Duration = chrono::Duration::hours(12);
 // Work directory - basically where all artifacts will be stored
 const WORKDIR: &str = "/var/tmp/bunge-bits";
 const TRANSCRIPT_CHUNK_DELIMITER: &str = "----END_OF_CHUNK----";
+// Target length of the audio chunks sent for transcription, 15 * 60 seconds
+const CHUNK_TARGET_SECS: u16 = 900;
+// Whisper rejects uploads over 25MB, stay on the safe side of MB vs MiB
+const WHISPER_MAX_UPLOAD_BYTES: u64 = 25_000_000;
+// Manifest written beside the audio chunks, listing them in order with their offsets
+const CHUNK_MANIFEST_FILE: &str = "manifest.json";
+// Maps positions in the chunked audio back to the video, written beside the manifest
+const TIME_MAP_FILE: &str = "time_map.json";
+// Silences at least this long (suspensions, dead air...) are cut before transcribing
+const MIN_RECESS_DURATION: Duration = Duration::from_secs(30);
+// How far a chunk boundary may move from the target length to land on a pause
+const CHUNK_CUT_TOLERANCE_SECS: u16 = 60;
+// Captions are stored per stream in this sub-directory of the work directory
+const CAPTIONS_DIR: &str = "captions";
+// Audio clips of a summary's notable quotes and key moments are stored per stream in this
+// sub-directory
+const CLIPS_DIR: &str = "clips";
+// Lists a stream's clips with what they are of, written beside them
+const CLIP_MANIFEST_FILE: &str = "clips.json";
+// Context kept around a quote when clipping it, on each side
+const QUOTE_CLIP_PADDING: Duration = Duration::from_secs(1);
+// Key moments are located less precisely than quotes, so they are given more context
+const MOMENT_CLIP_PADDING: Duration = Duration::from_secs(3);
+const CLIP_FADE: Duration = Duration::from_millis(500);
+// Clips are listened to as they are rather than transcribed, 128kbps stereo
+const CLIP_BIT_RATE: u32 = 128_000;
+const CLIP_CHANNELS: u32 = 2;
+// Minimum share of the stream's advertised duration a download must cover to be considered complete
+const MIN_AUDIO_COVERAGE: f64 = 0.98;
 // leave ~18k tokens for system/user prompts and model response
 const GPT4O_CONTEXT_LIMIT: usize = 128_000 - 18_000;
 
@@ -51,6 +101,8 @@ pub static RE_NUMBER_CHAIN: LazyLock<Regex> =
 // Numeric-only garbage lines like "1.0-1-1-1-1-1-1"
 pub static RE_NUMERIC_LINE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?m)^[\d.\-, ]{10,}$").unwrap());
+// Array indices in a JSON path, e.g. the `[3]` of `tabs[3]`
+static RE_PATH_INDEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\d+\]").unwrap());
 
 /// Fetches and processes a batch of Kenyan parliamentary video streams.
 ///
@@ -61,87 +113,378 @@ pub static RE_NUMERIC_LINE: LazyLock<Regex> =
 /// It limits processing to the `max_streams` most recent unprocessed videos.
 #[tracing::instrument]
 pub async fn fetch_and_process_streams(max_streams: usize) -> anyhow::Result<()> {
-    let client = &CLIENT;
-    let ytdlp = &YTDLP;
-    let openai = &OPENAI;
+    let db = init_datastore().await?;
+    process_streams(&db, max_streams, None).await
+}
 
-    let db_url = std::env::var("DATABASE_URL").context("DATABASE_URL not set")?;
-    let db = DataStore::init(&db_url)
-        .await
-        .context("Failed to initialize database")?;
+/// Processes past sittings streamed since `since`, oldest first, `max_streams` at a time.
+///
+/// Unlike [`fetch_and_process_streams`], which stops looking at the first page with an already
+/// processed stream or streams older than [`DISCOVERY_WINDOW`], this walks back through the
+/// channel's streams until `since`.
+#[tracing::instrument]
+pub async fn backfill_streams(since: DateTime<Utc>, max_streams: usize) -> anyhow::Result<()> {
+    let db = init_datastore().await?;
+    process_streams(&db, max_streams, Some(since)).await
+}
+
+/// Processes streams discovered live, or scheduled to start by now, once they're archived, so
+/// they're processed soon after ending rather than on the next regular run.
+///
+/// Only these streams are processed, and only once discovery lists them as archived, as a
+/// stream can stay live for hours or its start be postponed long past its schedule.
+#[tracing::instrument]
+pub async fn process_ended_streams(max_streams: usize) -> anyhow::Result<()> {
+    let db = init_datastore().await?;
+    let due = db.get_due_scheduled_streams(Utc::now()).await?;
 
-    let yt_html_document = client
-        .get(YOUTUBE_STREAM_URL)
-        .header("Accept-Language", "en-US,en;q=0.9")
-        .send()
+    if due.is_empty() {
+        tracing::debug!("No live or started streams to check on");
+        return Ok(());
+    }
+
+    tracing::info!(
+        video_ids = ?due.iter().map(|s| s.video_id.as_str()).collect::<Vec<_>>(),
+        "Checking whether live streams have ended"
+    );
+    let due_ids = due
+        .into_iter()
+        .map(|stream| stream.video_id)
+        .collect::<HashSet<_>>();
+    let ended = discover_all_streams(&db, None)
         .await?
-        .text()
-        .await?;
+        .into_iter()
+        .filter(|stream| due_ids.contains(&stream.video_id))
+        .collect::<Vec<_>>();
 
-    match extract_json_from_script(&yt_html_document) {
-        Ok(json) => {
-            let streams = parse_streams(&json)?;
-            tracing::info!(count = streams.len(), "Processing streams");
+    if ended.is_empty() {
+        tracing::debug!("No live or started streams have been archived yet");
+        return Ok(());
+    }
 
-            // This is where initially downloaded audio by yt-dlp is saved
-            let audio_download_path = PathBuf::from(format!("{WORKDIR}/audio"));
+    process_discovered_streams(&db, max_streams, ended).await
+}
 
-            let mut streams = sort_and_filter_existing_streams(max_streams, &db, streams).await?;
+async fn init_datastore() -> anyhow::Result<DataStore> {
+    let db_url = std::env::var("DATABASE_URL").context("DATABASE_URL not set")?;
+    DataStore::init(&db_url)
+        .await
+        .context("Failed to initialize database")
+}
 
-            if streams.is_empty() {
-                tracing::info!("No streams to process at this time");
-                return Ok(());
+async fn process_streams(
+    db: &DataStore,
+    max_streams: usize,
+    backfill_since: Option<DateTime<Utc>>,
+) -> anyhow::Result<()> {
+    let streams = discover_all_streams(db, backfill_since).await?;
+    process_discovered_streams(db, max_streams, streams).await
+}
+
+/// Discovers the archived streams of every configured source, see [`discover_streams`], and
+/// keeps track of their upcoming and live ones.
+async fn discover_all_streams(
+    db: &DataStore,
+    backfill_since: Option<DateTime<Utc>>,
+) -> anyhow::Result<Vec<Stream>> {
+    let sources = Source::from_env()?;
+    // identifies as the web client version the first source's page is currently served with
+    let innertube = &InnerTubeClient::discover(CLIENT.clone(), &sources[0].url()).await;
+    let mut streams = Vec::new();
+    let mut scheduled = Vec::new();
+    let mut failed_sources = 0;
+
+    for source in &sources {
+        match discover_streams(innertube, db, source, backfill_since).await {
+            Ok((found, found_scheduled)) => {
+                tracing::info!(
+                    source = %source.name,
+                    count = found.len(),
+                    scheduled = found_scheduled.len(),
+                    "Discovered streams"
+                );
+                streams.extend(found);
+                scheduled.extend(found_scheduled);
             }
+            Err(e) => {
+                failed_sources += 1;
+                tracing::error!(source = %source.name, error = ?e, "Failed to discover streams");
+            }
+        }
+    }
 
-            streams.par_iter_mut().try_for_each(|stream| {
-                handle_stream_audio(stream, audio_download_path.clone(), ytdlp)
-            })?;
+    if failed_sources == sources.len() {
+        bail!("Failed to discover streams from all {failed_sources} sources");
+    }
 
-            transcribe_streams(&streams, openai).await?;
+    // a stream listed by several sources is recorded against the first one configured
+    let streams = streams
+        .into_iter()
+        .unique_by(|stream| stream.video_id.clone())
+        .collect::<Vec<_>>();
+    let scheduled = scheduled
+        .into_iter()
+        .unique_by(|stream| stream.video_id.clone())
+        .collect::<Vec<_>>();
+    track_scheduled_streams(db, &streams, &scheduled, failed_sources == 0).await?;
 
-            summarize_streams(&mut streams, Arc::new(OPENAI.clone()), &db).await?;
-        }
-        Err(e) => {
-            tracing::error!(error = ?e,  "Error extracing ytInitialData from the html document");
-            bail!(
-                "Failed to extract ytInitialData from html document: {:?}",
-                e
-            );
-        }
+    Ok(streams)
+}
+
+/// Downloads, transcribes, summarizes and clips the `max_streams` oldest of `streams` that
+/// haven't been processed yet.
+async fn process_discovered_streams(
+    db: &DataStore,
+    max_streams: usize,
+    streams: Vec<Stream>,
+) -> anyhow::Result<()> {
+    let ytdlp = &YTDLP;
+    let openai = &OPENAI;
+
+    tracing::info!(count = streams.len(), "Processing streams");
+
+    // This is where initially downloaded audio by yt-dlp is saved
+    let audio_download_path = PathBuf::from(format!("{WORKDIR}/audio"));
+
+    let mut streams = sort_and_filter_existing_streams(max_streams, db, streams).await?;
+
+    if streams.is_empty() {
+        tracing::info!("No streams to process at this time");
+        return Ok(());
     }
 
+    let audio_statuses = streams
+        .par_iter_mut()
+        .map(|stream| handle_stream_audio(stream, audio_download_path.clone(), ytdlp))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // drop streams whose audio can't be fetched right now, they will be retried on a later run
+    let mut audio_statuses = audio_statuses.into_iter();
+    streams.retain(|_| matches!(audio_statuses.next(), Some(AudioStatus::Ready)));
+
+    if streams.is_empty() {
+        tracing::info!("No stream audio available to process at this time");
+        return Ok(());
+    }
+
+    transcribe_streams(&streams, openai).await?;
+
+    summarize_streams(&mut streams, Arc::new(OPENAI.clone()), db).await?;
+
+    // needs the downloaded audio, so before it's cleaned up
+    clip_summary_highlights(&streams, ytdlp);
+
     cleanup_audio_dir();
 
     Ok(())
 }
 
+/// Records upcoming and live streams, forgetting them once archived, so that
+/// [`process_ended_streams`] knows when to look for newly archived streams.
+///
+/// Streams no run has seen in [`SCHEDULED_STREAM_TTL`] are forgotten too, unless a source
+/// couldn't be checked this time around.
+async fn track_scheduled_streams(
+    db: &DataStore,
+    archived: &[Stream],
+    scheduled: &[ScheduledStream],
+    all_sources_checked: bool,
+) -> anyhow::Result<()> {
+    if !scheduled.is_empty() {
+        db.upsert_scheduled_streams(scheduled).await?;
+    }
+
+    let archived_ids = archived
+        .iter()
+        .map(|stream| stream.video_id.as_str())
+        .collect::<Vec<_>>();
+    db.delete_scheduled_streams(&archived_ids).await?;
+
+    if all_sources_checked {
+        let stale = db
+            .delete_stale_scheduled_streams(Utc::now() - SCHEDULED_STREAM_TTL)
+            .await?;
+        if stale > 0 {
+            tracing::info!(stale, "Forgot scheduled streams that are no longer listed");
+        }
+    }
+
+    Ok(())
+}
+
+/// Walks a source's streams from the newest page back, collecting streams until reaching
+/// `backfill_since` or, without one, a page with an already processed stream or streams older
+/// than [`DISCOVERY_WINDOW`].
+///
+/// Returns the archived streams, and the upcoming and live ones separately.
+#[tracing::instrument(skip(innertube, db, source), fields(source = %source.name))]
+async fn discover_streams(
+    innertube: &InnerTubeClient,
+    db: &DataStore,
+    source: &Source,
+    backfill_since: Option<DateTime<Utc>>,
+) -> anyhow::Result<(Vec<Stream>, Vec<ScheduledStream>)> {
+    let endpoint = source
+        .browse_endpoint(innertube)
+        .await
+        .inspect_err(report_parse_error)?;
+    // left untyped for the parser, see `InnerTubeClient::browse`
+    let initial_data = innertube
+        .browse::<Value>(&BrowseRequest::Endpoint(endpoint))
+        .await?;
+
+    // without the window, a fresh database or a newly added source would be walked back
+    // through its whole history on every run, starving new sittings behind the oldest ones
+    let cutoff = backfill_since.unwrap_or_else(|| Utc::now() - DISCOVERY_WINDOW);
+    let is_before_cutoff = |stream: &Stream| {
+        stream
+            .timestamp_from_time_ago()
+            .is_some_and(|streamed_at| streamed_at < cutoff)
+    };
+
+    let mut page = source
+        .parse_first_page(&initial_data)
+        .inspect_err(report_parse_error)?;
+    let mut streams = Vec::new();
+    let mut scheduled = Vec::new();
+
+    for page_number in 1.. {
+        let reached_known = page.streams.iter().any(is_before_cutoff)
+            || (backfill_since.is_none() && {
+                let video_ids = page
+                    .streams
+                    .iter()
+                    .map(|s| s.video_id.as_str())
+                    .collect::<Vec<_>>();
+                !db.get_existing_stream_ids(&video_ids).await?.is_empty()
+            });
+        streams.append(&mut page.streams);
+        scheduled.append(&mut page.scheduled);
+
+        let Some(token) = page.continuation.take() else {
+            break;
+        };
+        if reached_known || page_number >= MAX_STREAM_PAGES {
+            break;
+        }
+
+        tracing::debug!(page_number, "Fetching older streams");
+        let response = innertube
+            .browse::<Value>(&BrowseRequest::Continuation(token))
+            .await?;
+        page = parse_continuation_page(&response).inspect_err(report_parse_error)?;
+    }
+
+    // pages can overlap when a stream is published while walking them
+    let streams = streams
+        .into_iter()
+        .filter(|stream| !is_before_cutoff(stream))
+        .unique_by(|stream| stream.video_id.clone())
+        .map(|stream| {
+            let category = source.category_of(&stream);
+            tracing::debug!(video_id = %stream.video_id, %category, "Discovered stream");
+            Stream {
+                source: Some(source.name.clone()),
+                category: Some(category),
+                ..stream
+            }
+        })
+        .collect();
+    let scheduled = scheduled
+        .into_iter()
+        .map(|stream| ScheduledStream {
+            source: Some(source.name.clone()),
+            ..stream
+        })
+        .collect();
+
+    Ok((streams, scheduled))
+}
+
+/// Raises a YouTube structure change to Sentry under its own issue per JSON path, apart from
+/// the ordinary errors and empty results of a run.
+///
+/// Issues are grouped by the path with its array indices collapsed to `[]`, as in
+/// [`crate::fixtures::structure_paths`], so the same change on another tab or item doesn't open a
+/// new issue.
+fn report_structure_change(path: &str, reason: &str) {
+    tracing::warn!(path, reason, "YouTube data structure changed");
+    let structure_path = RE_PATH_INDEX.replace_all(path, "[]");
+    sentry::with_scope(
+        |scope| {
+            scope.set_tag("youtube.structure_path", &structure_path);
+            scope.set_fingerprint(Some(&["youtube-structure-changed", &*structure_path][..]));
+        },
+        || {
+            sentry::capture_message(
+                &format!("YouTube data structure changed at {path}: {reason}"),
+                sentry::Level::Error,
+            )
+        },
+    );
+}
+
+/// Reports parser errors that point at a structure change, see [`report_structure_change`].
+fn report_parse_error(error: &Error) {
+    if let Error::StructureChanged { path, reason } = error {
+        report_structure_change(path, reason);
+    }
+}
+
+/// Outcome of preparing a stream's audio for transcription
+#[derive(Debug, PartialEq, Eq)]
+enum AudioStatus {
+    /// Audio was downloaded, cleaned and chunked
+    Ready,
+    /// The video can't be downloaded at the moment (private, geo-blocked, still live...)
+    Skipped,
+}
+
+/// Returns the version of the yt-dlp binary used by the pipeline.
+pub fn ytdlp_version() -> anyhow::Result<String> {
+    YTDLP.version().context("Failed to get yt-dlp version")
+}
+
 #[tracing::instrument(skip(stream, ytdlp))]
 fn handle_stream_audio(
     stream: &mut Stream,
     audio_download_path: PathBuf,
     ytdlp: &YtDlp,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<AudioStatus> {
     let youtube_stream = format!("https://youtube.com/watch?v={}", stream.video_id);
 
     // construct all necessary paths
     let base_name = &stream.video_id;
     let audio_output_template = audio_download_path.join(format!("{base_name}.%(ext)s"));
     let audio_mp3_path = audio_download_path.join(format!("{base_name}.mp3"));
-
-    // intermediate cleaned file paths
-    let denoised_path = audio_download_path.join(format!("{base_name}_denoised.mp3"));
-    let normalized_path = audio_download_path.join(format!("{base_name}_normalized.mp3"));
-    let trimmed_path = audio_download_path.join(format!("{base_name}_trimmed.mp3"));
+    // lossless, since it gets re-encoded once more while conditioning
+    let condensed_path = audio_download_path.join(format!("{base_name}_condensed.flac"));
 
     let chunked_audio_path = PathBuf::from(format!("{WORKDIR}/audio/{base_name}"));
 
     // download audio if needed
     if !audio_mp3_path.exists() {
-        if let Err(e) = ytdlp
-            .download_audio(&youtube_stream, "mp3", &audio_output_template)
-            .inspect_err(|e| tracing::error!(error = ?e, "Failed to download audio"))
-        {
-            bail!("Failed to download audio: {:?}", e);
+        match ytdlp.download_audio(&youtube_stream, "mp3", &audio_output_template) {
+            Ok(()) => {}
+            Err(
+                e @ (YtDlpError::VideoUnavailable(_)
+                | YtDlpError::GeoBlocked(_)
+                | YtDlpError::LiveNotFinished(_)),
+            ) => {
+                tracing::warn!(error = ?e, video_id = %stream.video_id, "Skipping video, audio is not downloadable");
+                return Ok(AudioStatus::Skipped);
+            }
+            Err(e @ (YtDlpError::SignInRequired(_) | YtDlpError::CookiesExpired(_))) => {
+                tracing::error!(error = ?e, "yt-dlp cookies need to be refreshed");
+                bail!(
+                    "Failed to download audio, YTDLP_COOKIES_PATH needs refreshing: {:?}",
+                    e
+                );
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "Failed to download audio");
+                bail!("Failed to download audio: {:?}", e);
+            }
         }
 
         if !audio_mp3_path.exists() {
@@ -154,31 +497,117 @@ fn handle_stream_audio(
         tracing::debug!("Audio already exists at {:?}", audio_mp3_path);
     }
 
-    // perform cleanup if final trimmed audio does not exist
-    if !trimmed_path.exists() {
-        ytdlp.denoise_audio(&audio_mp3_path, &denoised_path)?;
-        ytdlp.normalize_volume(&denoised_path, &normalized_path)?;
-        ytdlp.trim_silence(&normalized_path, &trimmed_path)?;
-    } else {
-        tracing::debug!("Cleaned audio already exists at {:?}", trimmed_path);
-    }
+    // clean up and split if chunks not already present, the manifest is only written
+    // once splitting succeeded
+    let chunk_manifest_path = chunked_audio_path.join(CHUNK_MANIFEST_FILE);
 
-    // split if chunks not already present
-    let chunk_exists = std::fs::read_dir(&chunked_audio_path)
-        .map(|mut entries| entries.any(|e| e.is_ok()))
-        .unwrap_or(false);
+    if !chunk_manifest_path.exists() {
+        if let Err(e) = verify_audio_is_complete(ytdlp, &audio_mp3_path, &stream.duration) {
+            // remove the partial download so that it's fetched again on the next run
+            let _ = remove_file(&audio_mp3_path);
+            return Err(e);
+        }
 
-    if !chunk_exists {
+        // discard leftovers of an interrupted split, ffmpeg won't overwrite them
+        let _ = remove_file(&condensed_path);
+        let _ = remove_dir_all(&chunked_audio_path);
         create_dir_all(&chunked_audio_path)?;
-        ytdlp.split_audio_to_chunks(
-            &trimmed_path,
-            900, // 15 * 60 seconds
-            chunked_audio_path.join(format!("{base_name}_%03d.mp3")),
+
+        // cut out suspensions and dead air so we don't pay to transcribe them, keeping track
+        // of what was removed to map chunk offsets back to the video.
+        //
+        // This costs more than the single conditioning pass alone: the download is decoded once
+        // to find the silences and once more to write the lossless intermediate, which is then
+        // decoded twice itself, to find pauses to cut at and to write the chunks. Hours of
+        // recess not sent to Whisper are worth the extra decoding.
+        let audio_config = AudioProcessingConfig::default();
+        // the intermediate stays lossless, whatever codec the chunks are encoded with
+        let condense_config = AudioProcessingConfig {
+            codec: None,
+            ..audio_config.clone()
+        };
+        let time_map = ytdlp.remove_long_silences(
+            &audio_mp3_path,
+            MIN_RECESS_DURATION,
+            &condensed_path,
+            &condense_config,
         )?;
+        tracing::info!(removed = ?time_map.removed_duration(), "Removed long silences");
+        time_map.write(chunked_audio_path.join(TIME_MAP_FILE))?;
+
+        if let Ok(info) = ytdlp.probe(&condensed_path) {
+            tracing::info!(
+                duration = ?info.duration,
+                chunks = info.chunk_count(Duration::from_secs(CHUNK_TARGET_SECS.into())),
+                "Conditioning and splitting audio into chunks"
+            );
+        }
+
+        // denoise and normalize in a single ffmpeg pass, cutting at pauses so that words aren't
+        // split across chunks, then re-split any chunk Whisper would reject. The leading silence
+        // is already gone, trimming what padding is left would shift chunks off the time map
+        let chunk_config = AudioProcessingConfig {
+            trim_leading_silence: false,
+            ..audio_config
+        };
+        match ytdlp
+            .condition_and_segment_at_silences(
+                &condensed_path,
+                CHUNK_TARGET_SECS,
+                CHUNK_CUT_TOLERANCE_SECS,
+                chunked_audio_path.join(format!("{base_name}_%03d.mp3")),
+                &chunk_config,
+            )
+            .and_then(|chunks| {
+                ytdlp.fit_chunks_to_size(chunks, WHISPER_MAX_UPLOAD_BYTES, &chunk_config)
+            }) {
+            Ok(chunks) => {
+                tracing::debug!(
+                    cut_points = ?chunks.iter().skip(1).map(|c| c.start).collect::<Vec<_>>(),
+                    "Split audio at pauses"
+                );
+                AudioChunk::write_manifest(&chunks, &chunk_manifest_path)?;
+                let _ = remove_file(&condensed_path);
+            }
+            Err(e) => {
+                let _ = remove_dir_all(&chunked_audio_path);
+                return Err(e.into());
+            }
+        }
     } else {
         tracing::debug!("Chunks already exist at {:?}", chunked_audio_path);
     }
 
+    Ok(AudioStatus::Ready)
+}
+
+/// Checks that downloaded audio covers the stream's advertised duration, catching
+/// truncated downloads before any further processing.
+fn verify_audio_is_complete(
+    ytdlp: &YtDlp,
+    audio_path: &Path,
+    expected_duration: &str,
+) -> anyhow::Result<()> {
+    let info = ytdlp
+        .probe(audio_path)
+        .with_context(|| format!("Failed to probe audio at {}", audio_path.display()))?;
+
+    if info.audio_stream().is_none() {
+        bail!("No audio stream found in {}", audio_path.display());
+    }
+
+    if let Some(expected_secs) = parse_duration_to_seconds(expected_duration) {
+        let actual_secs = info.duration.as_secs_f64();
+        if actual_secs < expected_secs as f64 * MIN_AUDIO_COVERAGE {
+            bail!(
+                "Incomplete audio download at {}: got {:.0}s, expected {}s",
+                audio_path.display(),
+                actual_secs,
+                expected_secs
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -191,38 +620,267 @@ async fn transcribe_streams(streams: &[Stream], openai: &OpenAiClient) -> anyhow
             .append(true)
             .open(format!("{WORKDIR}/{}.txt", stream.video_id))?;
 
-        let mut entries = std::fs::read_dir(&audio_chunks_path)
-            .context("Failed to read dir")?
-            .collect::<Result<Vec<_>, _>>()
-            .context("Failed to collect dir entries")?;
-
-        // fs::read_dir doesn't guarantee sorted dir contents, hence the need to
-        // perform lexicographic sorting
-        entries.sort_by_key(|entry| entry.path());
+        // the manifest lists chunks in order, the time map locates them in the sitting
+        let chunks = AudioChunk::read_manifest(audio_chunks_path.join(CHUNK_MANIFEST_FILE))
+            .context("Failed to read audio chunk manifest")?;
+        let time_map = TimeMap::read(audio_chunks_path.join(TIME_MAP_FILE))
+            .context("Failed to read audio time map")?;
+        let mut captions = Vec::new();
+
+        for chunk in chunks {
+            tracing::debug!(
+                index = chunk.index,
+                start = ?time_map.to_original(chunk.start),
+                end = ?time_map.to_original(chunk.end),
+                "Transcribing chunk"
+            );
 
-        for entry in entries {
-            match transcribe_audio(entry.path(), openai).await {
+            match transcribe_audio(chunk.path.clone(), openai).await {
                 Ok(transcription) => {
-                    write!(transcript_file, "{transcription}")?;
+                    writeln!(transcript_file, "{}", transcription.text)?;
                     writeln!(transcript_file, "{TRANSCRIPT_CHUNK_DELIMITER}")?;
+                    // whisper times segments from the start of the chunk
+                    captions.extend(
+                        transcription
+                            .segments()
+                            .iter()
+                            .map(|segment| segment.to_original(chunk.start, &time_map)),
+                    );
                 }
                 Err(err) => {
-                    tracing::error!(error = ?err, "Skipping failed chunk {}", entry.path().display());
+                    tracing::error!(error = ?err, "Skipping failed chunk {}", chunk.path.display());
                     return Err(err);
                 }
             }
         }
+
+        write_captions(&stream.video_id, &captions)?;
+        std::fs::write(
+            transcript_segments_path(&stream.video_id),
+            serde_json::to_vec(&captions)?,
+        )?;
     }
 
     Ok(())
 }
 
+/// Path of a stream's timed transcript, with positions in the video
+fn transcript_segments_path(video_id: &str) -> PathBuf {
+    PathBuf::from(format!("{WORKDIR}/{video_id}.segments.json"))
+}
+
+/// Path of a stream's caption file in the given format
+pub(crate) fn caption_path(video_id: &str, format: SubtitleFormat) -> PathBuf {
+    PathBuf::from(format!(
+        "{WORKDIR}/{CAPTIONS_DIR}/{video_id}.{}",
+        format.extension()
+    ))
+}
+
+/// Directory of a stream's clips and their manifest
+pub(crate) fn clips_path(video_id: &str) -> PathBuf {
+    PathBuf::from(format!("{WORKDIR}/{CLIPS_DIR}/{video_id}"))
+}
+
+/// Path of the manifest listing a stream's clips
+pub(crate) fn clip_manifest_path(video_id: &str) -> PathBuf {
+    clips_path(video_id).join(CLIP_MANIFEST_FILE)
+}
+
+/// Stores a stream's captions as both WebVTT and SRT.
+fn write_captions(video_id: &str, segments: &[TranscriptSegment]) -> anyhow::Result<()> {
+    create_dir_all(format!("{WORKDIR}/{CAPTIONS_DIR}"))?;
+
+    for format in [SubtitleFormat::WebVtt, SubtitleFormat::Srt] {
+        let path = caption_path(video_id, format);
+        SubtitleWriter::new(format)
+            .write(segments, &path)
+            .with_context(|| format!("Failed to write captions to {}", path.display()))?;
+    }
+
+    tracing::info!(video_id, cues = segments.len(), "Stored captions");
+
+    Ok(())
+}
+
+/// Downloads and transcribes only the given section of a stream.
+///
+/// Useful for re-transcribing a region that came out garbled without fetching
+/// the whole multi-hour sitting again.
+#[tracing::instrument]
+pub async fn transcribe_stream_section(
+    video_id: &str,
+    section: TimeRange,
+) -> anyhow::Result<String> {
+    let youtube_stream = format!("https://youtube.com/watch?v={video_id}");
+    let sections_path = PathBuf::from(format!("{WORKDIR}/sections/{video_id}"));
+    create_dir_all(&sections_path)?;
+
+    let output_template = sections_path.join(format!(
+        "{video_id}_%(section_start)s-%(section_end)s.%(ext)s"
+    ));
+    let audio_paths = tokio::task::spawn_blocking(move || {
+        YTDLP.download_audio_sections(&youtube_stream, "mp3", &[section], output_template)
+    })
+    .await?
+    .with_context(|| format!("Failed to download section {section} of {video_id}"))?;
+
+    let audio_path = audio_paths
+        .into_iter()
+        .next()
+        .context("yt-dlp did not report the downloaded section")?;
+
+    transcribe_audio(audio_path, &OPENAI)
+        .await
+        .map(|transcription| transcription.text)
+}
+
+/// Cuts an audio clip for each of the notable quotes and key moments in a stream's summary,
+/// for sharing, and lists them in a manifest beside the clips.
+///
+/// Highlights are located in the stream's timed transcript. Those that can't be found or clipped
+/// are skipped, since the summary is complete without them.
+#[tracing::instrument(skip(streams, ytdlp))]
+fn clip_summary_highlights(streams: &[Stream], ytdlp: &YtDlp) {
+    for stream in streams {
+        let Some(summary_md) = &stream.summary_md else {
+            continue;
+        };
+        let quotes = notable_quotes(summary_md)
+            .into_iter()
+            .enumerate()
+            .map(|(i, quote)| (HighlightKind::Quote, i, quote));
+        let moments = key_moments(summary_md)
+            .into_iter()
+            .enumerate()
+            .map(|(i, moment)| (HighlightKind::KeyMoment, i, moment));
+        let highlights = quotes.chain(moments).collect::<Vec<_>>();
+        if highlights.is_empty() {
+            continue;
+        }
+
+        let segments = match std::fs::read(transcript_segments_path(&stream.video_id))
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(serde_json::from_slice::<Vec<TranscriptSegment>>(&json)?))
+        {
+            Ok(segments) => segments,
+            Err(e) => {
+                tracing::warn!(error = ?e, video_id = %stream.video_id, "No timed transcript to clip highlights from");
+                continue;
+            }
+        };
+
+        let audio_path = PathBuf::from(format!("{WORKDIR}/audio/{}.mp3", stream.video_id));
+        let clip_config = match shared_clip_config(ytdlp, &audio_path) {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!(error = ?e, video_id = %stream.video_id, "Failed to probe audio to clip highlights from");
+                continue;
+            }
+        };
+        let clips_dir = clips_path(&stream.video_id);
+        if let Err(e) = create_dir_all(&clips_dir) {
+            tracing::warn!(error = ?e, path = ?clips_dir, "Failed to create clips directory");
+            continue;
+        }
+
+        let mut clips = Vec::with_capacity(highlights.len());
+        for (kind, i, text) in highlights {
+            let located = match kind {
+                HighlightKind::Quote => locate_quote(&text, &segments, QUOTE_CLIP_PADDING),
+                HighlightKind::KeyMoment => locate_moment(&text, &segments, MOMENT_CLIP_PADDING),
+            };
+            let Some(range) = located else {
+                tracing::warn!(video_id = %stream.video_id, kind = kind.as_str(), %text, "Highlight not found in the transcript");
+                continue;
+            };
+
+            let file = format!("{}_{i:02}.mp3", kind.as_str());
+            let clip_path = clips_dir.join(&file);
+            match ytdlp.extract_clip(&audio_path, range, CLIP_FADE, &clip_path, &clip_config) {
+                Ok(()) => {
+                    tracing::info!(%range, path = %clip_path.display(), "Clipped highlight");
+                    clips.push(HighlightClip {
+                        kind,
+                        text,
+                        start_secs: range.start().as_secs_f64(),
+                        end_secs: range.end().as_secs_f64(),
+                        file,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(error = ?e, %range, %text, "Failed to clip highlight")
+                }
+            }
+        }
+
+        let manifest_path = clip_manifest_path(&stream.video_id);
+        if let Err(e) = serde_json::to_vec(&clips)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(std::fs::write(&manifest_path, json)?))
+        {
+            tracing::warn!(error = ?e, path = ?manifest_path, "Failed to write clip manifest");
+        }
+    }
+}
+
+/// Encoding of shared clips: unlike the 16kHz mono audio sent for transcription, they keep
+/// the sample rate of the downloaded audio, in stereo.
+fn shared_clip_config(ytdlp: &YtDlp, audio_path: &Path) -> anyhow::Result<AudioProcessingConfig> {
+    let sample_rate = ytdlp
+        .probe(audio_path)?
+        .audio_stream()
+        .and_then(|stream| stream.sample_rate)
+        .with_context(|| format!("No audio sample rate reported for {}", audio_path.display()))?;
+
+    Ok(AudioProcessingConfig {
+        sample_rate,
+        channels: CLIP_CHANNELS,
+        bit_rate: Some(CLIP_BIT_RATE),
+        ..Default::default()
+    })
+}
+
+/// The parts of Whisper's `verbose_json` transcription response that we use
+#[derive(Debug, Deserialize)]
+struct WhisperTranscription {
+    text: String,
+    #[serde(default)]
+    segments: Vec<WhisperSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperSegment {
+    /// Seconds from the start of the transcribed audio
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+impl WhisperTranscription {
+    fn segments(&self) -> Vec<TranscriptSegment> {
+        let secs = |secs: f64| Duration::try_from_secs_f64(secs).unwrap_or_default();
+
+        self.segments
+            .iter()
+            .filter(|segment| !segment.text.trim().is_empty())
+            .map(|segment| {
+                TranscriptSegment::new(secs(segment.start), secs(segment.end), segment.text.trim())
+            })
+            .collect()
+    }
+}
+
 #[tracing::instrument(skip(openai))]
-async fn transcribe_audio(audio_path: PathBuf, openai: &OpenAiClient) -> anyhow::Result<String> {
+async fn transcribe_audio(
+    audio_path: PathBuf,
+    openai: &OpenAiClient,
+) -> anyhow::Result<WhisperTranscription> {
+    // verbose output includes timed segments, used for captions
     let params = AudioTranscriptionParametersBuilder::default()
         .file(FileUpload::File(format!("{}", audio_path.display())))
         .model(TranscriptionModel::Whisper1.to_string())
-        .response_format(AudioOutputFormat::Text)
+        .response_format(AudioOutputFormat::VerboseJson)
         .build()?;
 
     let max_retries = 5;
@@ -233,20 +891,19 @@ async fn transcribe_audio(audio_path: PathBuf, openai: &OpenAiClient) -> anyhow:
 
         attempt += 1;
         match openai.audio().create_transcription(params.clone()).await {
-            Ok(result) => {
-                //XXX: Very basic check that it’s not a JSON error disguised as a string
-                if result.trim_start().starts_with('{') {
-                    tracing::warn!("Received unexpected JSON: {result}");
+            Ok(result) => match serde_json::from_str::<WhisperTranscription>(&result) {
+                Ok(transcription) => {
+                    tracing::info!("Transcription success for {}", audio_path.display());
+                    return Ok(transcription);
+                }
+                // e.g. a JSON error disguised as a successful response
+                Err(err) => {
+                    tracing::warn!(error = ?err, "Received unexpected response: {result}");
                     if attempt >= max_retries {
-                        bail!(
-                            "Received JSON error instead of transcription after {attempt} attempts"
-                        );
+                        bail!("Received no transcription after {attempt} attempts");
                     }
-                } else {
-                    tracing::info!("Transcription success for {}", audio_path.display());
-                    return Ok(result);
                 }
-            }
+            },
             Err(err) => {
                 tracing::warn!(attempt, error = ?err, "Transcription failed for {} (attempt ({}/{}))", audio_path.display(), attempt, max_retries);
                 if attempt >= max_retries {
@@ -358,13 +1015,7 @@ async fn summarize_stream(
                 content: ChatMessageContent::Text(
                     include_str!("../prompts/user_0.txt")
                         .replace("${{TITLE}}", &stream.title)
-                        .replace(
-                            "${{DATE}}",
-                            &stream
-                                .timestamp_from_time_ago()
-                                .map(|v| v.to_string())
-                                .unwrap_or_else(|| "${{DATE: inferred from summary}}".to_string()),
-                        ),
+                        .replace("${{DATE}}", &prompt_date(stream)),
                 ),
                 name: None,
             },
@@ -542,13 +1193,7 @@ Summaries:
 "#,
         include_str!("../prompts/user_2.txt")
             .replace("${{TITLE}}", &stream.title)
-            .replace(
-                "${{DATE}}",
-                &stream
-                    .timestamp_from_time_ago()
-                    .map(|v| v.to_string())
-                    .unwrap_or_else(|| "${{DATE: inferred from summary}}".to_string()),
-            ),
+            .replace("${{DATE}}", &prompt_date(stream)),
         summaries
     );
 
@@ -669,6 +1314,23 @@ pub async fn sort_and_filter_existing_streams(
     Ok(result)
 }
 
+/// The sitting's date for the prompts, e.g. "Tuesday, 10 June 2025 (afternoon session)",
+/// taken from the title or else approximated from when the stream was published.
+fn prompt_date(stream: &Stream) -> String {
+    let sitting = stream.sitting_info();
+
+    match (sitting.date, sitting.session) {
+        (Some(date), Some(session)) => {
+            format!("{} ({session} session)", date.format("%A, %-d %B %Y"))
+        }
+        (Some(date), None) => date.format("%A, %-d %B %Y").to_string(),
+        (None, _) => stream
+            .timestamp_from_time_ago()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "${{DATE: inferred from summary}}".to_string()),
+    }
+}
+
 fn count_tokens(text: &str) -> anyhow::Result<usize> {
     let bpe = cl100k_base()?;
     Ok(bpe.encode_with_special_tokens(text).len())
@@ -704,6 +1366,39 @@ pub fn cleanup_audio_dir() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn reads_whisper_segments() {
+        let response = r#"{
+            "task": "transcribe",
+            "language": "english",
+            "duration": 12.5,
+            "text": "Order, order. Please be seated.",
+            "segments": [
+                {"id": 0, "start": 0.0, "end": 2.5, "text": " Order, order."},
+                {"id": 1, "start": 2.5, "end": 4.0, "text": " "},
+                {"id": 2, "start": 4.0, "end": 6.25, "text": " Please be seated."}
+            ]
+        }"#;
+
+        let transcription = serde_json::from_str::<WhisperTranscription>(response).unwrap();
+
+        assert_eq!(
+            transcription.segments(),
+            vec![
+                TranscriptSegment::new(
+                    Duration::ZERO,
+                    Duration::from_millis(2_500),
+                    "Order, order."
+                ),
+                TranscriptSegment::new(
+                    Duration::from_secs(4),
+                    Duration::from_millis(6_250),
+                    "Please be seated."
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn removes_number_chains() {
         let input = "1.0-2-1.0-1-1-1-1-1-1.0-1\nSome actual content.";
@@ -0,0 +1,289 @@
+//! # Summary Highlights
+//!
+//! Locates the quotes and key moments picked out in a stream's summary within its timed
+//! transcript, so that the matching audio can be clipped and shared.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use ytdlp_bindings::{TimeRange, TranscriptSegment};
+
+/// Heading of the summary section listing quotes, see the summary prompts
+const NOTABLE_QUOTES_HEADING: &str = "## Notable Quotes";
+/// Heading of the summary section describing key moments, see the summary prompts
+const KEY_MOMENTS_HEADING: &str = "## Key Moments";
+/// Longest run of consecutive transcript segments a quote may span
+const MAX_QUOTE_SEGMENTS: usize = 3;
+/// Longest run of consecutive transcript segments a key moment may span
+const MAX_MOMENT_SEGMENTS: usize = 6;
+/// Share of a key moment's keywords the transcript has to mention for it to be located
+const MIN_MOMENT_MATCH: f64 = 0.6;
+
+/// What part of a summary a clip is of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightKind {
+    Quote,
+    KeyMoment,
+}
+
+impl HighlightKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HighlightKind::Quote => "quote",
+            HighlightKind::KeyMoment => "key_moment",
+        }
+    }
+}
+
+/// A clip of a quote or key moment, as listed in the manifest beside a stream's clips
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighlightClip {
+    pub kind: HighlightKind,
+    /// The quote, or the description of the key moment
+    pub text: String,
+    /// Where the clip starts in the video, in seconds
+    pub start_secs: f64,
+    /// Where the clip ends in the video, in seconds
+    pub end_secs: f64,
+    /// File name of the clip, beside the manifest
+    pub file: String,
+}
+
+/// The bullets of a summary's section, up to the next heading or rule
+fn section_bullets<'a>(summary_md: &'a str, heading: &'a str) -> impl Iterator<Item = &'a str> {
+    summary_md
+        .lines()
+        .skip_while(move |line| !line.trim().eq_ignore_ascii_case(heading))
+        .skip(1)
+        .take_while(|line| {
+            let line = line.trim();
+            !line.starts_with('#') && !line.starts_with("---")
+        })
+        .filter_map(|line| line.trim().strip_prefix("- "))
+}
+
+/// Extracts the quoted text from the bullets of a summary's "Notable Quotes" section,
+/// e.g. `Development must never come at the expense of human lives.` from
+/// `- *“Development must never come at the expense of human lives.”* – Hon. Makali Mulu`.
+pub fn notable_quotes(summary_md: &str) -> Vec<String> {
+    section_bullets(summary_md, NOTABLE_QUOTES_HEADING)
+        .filter_map(quoted_text)
+        .collect()
+}
+
+/// Extracts the bullets of a summary's "Key Moments" section, e.g. `The Speaker called for
+/// order after a heated exchange.`
+pub fn key_moments(summary_md: &str) -> Vec<String> {
+    section_bullets(summary_md, KEY_MOMENTS_HEADING)
+        .map(|bullet| bullet.replace("**", "").trim().to_string())
+        .filter(|bullet| !bullet.is_empty())
+        .collect()
+}
+
+/// The text between the first pair of (curly or straight) double quotes
+fn quoted_text(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once(['“', '"'])?;
+    let (text, _) = rest.split_once(['”', '"'])?;
+    let text = text.trim();
+
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Lowercase words without punctuation, to compare quotes with the transcript they came from
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Finds where a quote was said, padded by `padding` on both sides.
+///
+/// Summaries shorten quotes with ellipses, so only the longest uninterrupted part has to
+/// appear verbatim (ignoring case and punctuation) in up to three consecutive segments.
+pub fn locate_quote(
+    quote: &str,
+    segments: &[TranscriptSegment],
+    padding: Duration,
+) -> Option<TimeRange> {
+    let fragment = quote
+        .split('…')
+        .flat_map(|part| part.split("..."))
+        .map(normalized_words)
+        .max_by_key(|words| words.len())
+        .filter(|words| !words.is_empty())?;
+    let segment_words = segments
+        .iter()
+        .map(|segment| normalized_words(&segment.text))
+        .collect::<Vec<_>>();
+
+    for span in 1..=MAX_QUOTE_SEGMENTS {
+        for (i, window) in segment_words.windows(span).enumerate() {
+            let words = window.concat();
+            if words
+                .windows(fragment.len())
+                .any(|candidate| candidate == fragment.as_slice())
+            {
+                let start = segments[i].start.saturating_sub(padding);
+                let end = segments[i + span - 1].end + padding;
+                return TimeRange::new(start, end).ok();
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds where a key moment happened, padded by `padding` on both sides.
+///
+/// Key moments describe rather than quote what was said, so the moment is placed at the
+/// shortest run of up to six consecutive segments mentioning the most of its longer words,
+/// provided that's most of them.
+pub fn locate_moment(
+    moment: &str,
+    segments: &[TranscriptSegment],
+    padding: Duration,
+) -> Option<TimeRange> {
+    let keywords = normalized_words(moment)
+        .into_iter()
+        .filter(|word| word.chars().count() >= 4)
+        .collect::<HashSet<_>>();
+    if keywords.len() < 3 {
+        return None;
+    }
+    let segment_words = segments
+        .iter()
+        .map(|segment| normalized_words(&segment.text))
+        .collect::<Vec<_>>();
+
+    // (keywords mentioned, first segment, segments spanned)
+    let mut best: Option<(usize, usize, usize)> = None;
+    for first in 0..segments.len() {
+        let mut mentioned = HashSet::new();
+        for (span, words) in segment_words[first..]
+            .iter()
+            .take(MAX_MOMENT_SEGMENTS)
+            .enumerate()
+        {
+            mentioned.extend(words.iter().filter(|word| keywords.contains(*word)));
+            let candidate = (mentioned.len(), first, span + 1);
+            if best.is_none_or(|(most, _, shortest)| {
+                candidate.0 > most || (candidate.0 == most && candidate.2 < shortest)
+            }) {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    let (mentioned, first, span) = best?;
+    if (mentioned as f64) < keywords.len() as f64 * MIN_MOMENT_MATCH {
+        return None;
+    }
+
+    let start = segments[first].start.saturating_sub(padding);
+    let end = segments[first + span - 1].end + padding;
+    TimeRange::new(start, end).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUMMARY: &str = "## Key Moments
+
+- The Speaker called for order after a heated exchange.
+
+## Notable Quotes
+
+- *“Development must never come at the expense of human lives.”* – Hon. Makali Mulu
+- *“The continued detention of Miss Gaku's body... inhumane torture.”* – Hon. Irene Kasalu
+
+---
+
+Generated by Bunge Bits";
+
+    fn segment(start: u64, end: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment::new(Duration::from_secs(start), Duration::from_secs(end), text)
+    }
+
+    #[test]
+    fn extracts_notable_quotes() {
+        assert_eq!(
+            notable_quotes(SUMMARY),
+            vec![
+                "Development must never come at the expense of human lives.",
+                "The continued detention of Miss Gaku's body... inhumane torture.",
+            ]
+        );
+        assert!(notable_quotes("## Key Moments\n\n- \"Not a quote section\"").is_empty());
+    }
+
+    #[test]
+    fn extracts_key_moments() {
+        assert_eq!(
+            key_moments(SUMMARY),
+            vec!["The Speaker called for order after a heated exchange."]
+        );
+        assert_eq!(
+            key_moments(
+                "## Key Moments\n- **Walkout**: The minority side walked out.\n## Notable Quotes"
+            ),
+            vec!["Walkout: The minority side walked out."]
+        );
+    }
+
+    #[test]
+    fn locates_quotes_across_segments() {
+        let segments = vec![
+            segment(100, 104, "Thank you, Hon. Speaker."),
+            segment(104, 110, "The continued detention of Miss Gaku's"),
+            segment(110, 118, "body amounts to inhumane torture, Hon. Speaker."),
+        ];
+        let padding = Duration::from_secs(1);
+
+        assert_eq!(
+            locate_quote(
+                "The continued detention of Miss Gaku's body... inhumane torture.",
+                &segments,
+                padding
+            ),
+            Some(TimeRange::new(Duration::from_secs(103), Duration::from_secs(119)).unwrap())
+        );
+        assert_eq!(
+            locate_quote("Thank you, hon. speaker", &segments, padding),
+            Some(TimeRange::new(Duration::from_secs(99), Duration::from_secs(105)).unwrap())
+        );
+        assert_eq!(locate_quote("Order, order!", &segments, padding), None);
+    }
+
+    #[test]
+    fn locates_key_moments_by_their_keywords() {
+        let segments = vec![
+            segment(100, 110, "The member for Kikuyu has the floor."),
+            segment(110, 118, "Point of order! This is a heated exchange,"),
+            segment(118, 125, "order, the Speaker is on his feet."),
+            segment(125, 140, "We now move to the next order."),
+        ];
+        let padding = Duration::from_secs(2);
+
+        assert_eq!(
+            locate_moment(
+                "The Speaker called for order after a heated exchange.",
+                &segments,
+                padding
+            ),
+            Some(TimeRange::new(Duration::from_secs(108), Duration::from_secs(127)).unwrap())
+        );
+        assert_eq!(
+            locate_moment(
+                "The Finance Bill was read a third time.",
+                &segments,
+                padding
+            ),
+            None
+        );
+        assert_eq!(locate_moment("Walkout.", &segments, padding), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! # Stream Sources
+//!
+//! The YouTube channels and playlists streams are discovered from, e.g. the main Parliament of
+//! Kenya channel alongside committee playlists and county assembly channels.
+//!
+//! Sources are read from the JSON file at `STREAM_SOURCES_PATH`, listed in order of priority:
+//! a stream found through several of them is recorded against the first.
+//!
+//! ```json
+//! [
+//!     { "name": "Parliament of Kenya", "kind": "channel", "handle": "@ParliamentofKenyaChannel" },
+//!     { "name": "Senate Committees", "kind": "playlist", "id": "PLx...", "category": "senate" }
+//! ]
+//! ```
+
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::Value;
+use stream_datastore::{Stream, StreamCategory};
+
+use crate::{
+    error::Error,
+    innertube::InnerTubeClient,
+    parser::{parse_playlist_page, parse_streams_page, ChannelTab, StreamsPage},
+    types::BrowseEndpoint,
+};
+
+/// A channel or playlist to discover streams from
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Source {
+    /// Recorded against every stream discovered through this source
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: SourceKind,
+    /// Category of the source's streams whose titles don't name one, e.g. `senate` or
+    /// `county_assembly:Nairobi City`
+    #[serde(default)]
+    pub category: Option<StreamCategory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SourceKind {
+    Channel {
+        /// The channel's handle, including the `@`
+        handle: String,
+        #[serde(default = "default_channel_tab")]
+        tab: ChannelTab,
+    },
+    Playlist {
+        /// The playlist's `list` id
+        id: String,
+    },
+}
+
+fn default_channel_tab() -> ChannelTab {
+    ChannelTab::Streams
+}
+
+impl Source {
+    /// The Parliament of Kenya channel's streams, used when no sources are configured
+    pub fn parliament_of_kenya() -> Self {
+        Source {
+            name: "Parliament of Kenya".to_string(),
+            kind: SourceKind::Channel {
+                handle: "@ParliamentofKenyaChannel".to_string(),
+                tab: ChannelTab::Streams,
+            },
+            category: None,
+        }
+    }
+
+    /// Reads the sources configured at `STREAM_SOURCES_PATH`, falling back to
+    /// [`Source::parliament_of_kenya`] when it's not set.
+    pub fn from_env() -> anyhow::Result<Vec<Self>> {
+        match std::env::var("STREAM_SOURCES_PATH") {
+            Ok(path) if !path.is_empty() => Self::from_file(path),
+            _ => Ok(vec![Self::parliament_of_kenya()]),
+        }
+    }
+
+    /// Reads a JSON list of sources.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Vec<Self>> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read stream sources from {}", path.display()))?;
+        let sources = serde_json::from_str::<Vec<Source>>(&json)
+            .with_context(|| format!("Invalid stream sources in {}", path.display()))?;
+
+        if sources.is_empty() {
+            anyhow::bail!("No stream sources configured in {}", path.display());
+        }
+
+        Ok(sources)
+    }
+
+    /// The page listing the source's streams, newest first
+    pub fn url(&self) -> String {
+        match &self.kind {
+            SourceKind::Channel { handle, tab } => {
+                let tab = match tab {
+                    ChannelTab::Streams => "streams",
+                    ChannelTab::Videos => "videos",
+                };
+                format!("https://www.youtube.com/{handle}/{tab}")
+            }
+            SourceKind::Playlist { id } => format!("https://www.youtube.com/playlist?list={id}"),
+        }
+    }
+
+    /// The InnerTube endpoint browsing [`Source::url`], resolved for channels.
+    pub async fn browse_endpoint(
+        &self,
+        innertube: &InnerTubeClient,
+    ) -> Result<BrowseEndpoint, Error> {
+        match &self.kind {
+            SourceKind::Channel { .. } => innertube.resolve_url(&self.url()).await,
+            SourceKind::Playlist { id } => Ok(BrowseEndpoint {
+                browse_id: format!("VL{id}"),
+                params: None,
+            }),
+        }
+    }
+
+    /// Parses the first page of streams from the `ytInitialData` of [`Source::url`], or the
+    /// browse response for its [`Source::browse_endpoint`].
+    pub fn parse_first_page(&self, json: &Value) -> Result<StreamsPage, Error> {
+        match &self.kind {
+            SourceKind::Channel { tab, .. } => parse_streams_page(json, *tab),
+            SourceKind::Playlist { .. } => parse_playlist_page(json),
+        }
+    }
+
+    /// The category named by a stream's title, or else the source's default.
+    pub fn category_of(&self, stream: &Stream) -> StreamCategory {
+        match (StreamCategory::from_title(&stream.title), &self.category) {
+            (StreamCategory::Other, Some(category)) => category.clone(),
+            (category, _) => category,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_channels_and_playlists() {
+        let sources = serde_json::from_str::<Vec<Source>>(
+            r#"[
+                { "name": "Parliament of Kenya", "kind": "channel", "handle": "@ParliamentofKenyaChannel" },
+                { "name": "Senate Committees", "kind": "playlist", "id": "PLsenate", "category": "senate" },
+                { "name": "Uploads", "kind": "channel", "handle": "@ParliamentofKenyaChannel", "tab": "videos" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(sources[0], Source::parliament_of_kenya());
+        assert_eq!(
+            sources[0].url(),
+            "https://www.youtube.com/@ParliamentofKenyaChannel/streams"
+        );
+        assert_eq!(
+            sources[1].url(),
+            "https://www.youtube.com/playlist?list=PLsenate"
+        );
+        assert_eq!(
+            sources[2].url(),
+            "https://www.youtube.com/@ParliamentofKenyaChannel/videos"
+        );
+
+        let committee_sitting = Stream {
+            title: "Standing Committee on Finance and Planning".to_string(),
+            ..Default::default()
+        };
+        let uncategorised = Stream {
+            title: "Public Hearing on the Finance Bill".to_string(),
+            ..Default::default()
+        };
+        let house_sitting = Stream {
+            title: "NATIONAL ASSEMBLY | TUESDAY 10TH JUNE 2025".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            sources[1].category_of(&uncategorised),
+            StreamCategory::Senate
+        );
+        assert_eq!(
+            sources[1].category_of(&committee_sitting),
+            StreamCategory::Committee {
+                name: "Standing Committee on Finance and Planning".to_string()
+            }
+        );
+        assert_eq!(
+            sources[1].category_of(&house_sitting),
+            StreamCategory::NationalAssembly
+        );
+        assert_eq!(
+            sources[0].category_of(&uncategorised),
+            StreamCategory::Other
+        );
+    }
+}
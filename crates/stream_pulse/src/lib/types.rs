@@ -1,6 +1,7 @@
 //! # Yt Types
 //!
-//! This module contains type definitions for working with the `ytInitialData` object.
+//! This module contains type definitions for working with the `ytInitialData` object, and the
+//! InnerTube API responses sharing its structure.
 //!
 //! It provides a small sub-set of structs that matches its structure, allowing for easy
 //! deserialization and manipulation of video data.
@@ -27,6 +28,26 @@ pub struct VideoRenderer {
     pub length_text: Option<AccessibilityText>,
     #[serde(rename = "upcomingEventData")]
     pub upcoming_event_data: Option<UpcomingEventData>,
+    /// Start of the video's description, in runs setting apart the words searched for
+    #[serde(rename = "descriptionSnippet")]
+    pub description_snippet: Option<TextRuns>,
+    pub badges: Option<Vec<Badge>>,
+}
+
+/// A video in a playlist's `playlistVideoListRenderer`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaylistVideoRenderer {
+    #[serde(rename = "videoId")]
+    pub video_id: String,
+    pub thumbnail: Option<Thumbnail>,
+    pub title: TextRuns,
+    #[serde(rename = "lengthText")]
+    pub length_text: Option<AccessibilityText>,
+    /// View count and publish date, e.g. `["1.2K views", " • ", "Streamed 3 days ago"]`
+    #[serde(rename = "videoInfo")]
+    pub video_info: Option<TextRuns>,
+    #[serde(rename = "upcomingEventData")]
+    pub upcoming_event_data: Option<UpcomingEventData>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -75,6 +96,19 @@ pub struct ThumbnailItem {
     pub height: i32,
 }
 
+/// A label shown on a video, e.g. "CC" when it has captions, or "New"
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Badge {
+    #[serde(rename = "metadataBadgeRenderer")]
+    pub metadata_badge_renderer: Option<MetadataBadgeRenderer>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetadataBadgeRenderer {
+    pub label: Option<String>,
+    pub style: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpcomingEventData {
     #[serde(rename = "isReminderSet")]
@@ -84,3 +118,26 @@ pub struct UpcomingEventData {
     #[serde(rename = "upcomingEventText")]
     pub upcoming_event_text: TextRuns,
 }
+
+/// Response of the InnerTube `navigation/resolve_url` endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolveUrlResponse {
+    pub endpoint: ResolvedEndpoint,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolvedEndpoint {
+    /// Missing when the URL isn't of a channel or playlist, e.g. a video
+    #[serde(rename = "browseEndpoint")]
+    pub browse_endpoint: Option<BrowseEndpoint>,
+}
+
+/// What the InnerTube `browse` endpoint is asked for, e.g. a channel's streams tab
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrowseEndpoint {
+    /// The channel's `UC...` id, or a playlist's id prefixed with `VL`
+    #[serde(rename = "browseId")]
+    pub browse_id: String,
+    /// Encoded tab selection, e.g. the streams tab of a channel
+    pub params: Option<String>,
+}
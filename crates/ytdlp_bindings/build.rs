@@ -1,14 +1,64 @@
 use std::env;
-use std::fs::File;
-use std::io::{copy, Write};
-use std::path::Path;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
 
 /// The yt-dlp version based off their github releases
 /// <https://github.com/yt-dlp/yt-dlp/releases>
 const YTDLP_RELEASE: &str = "2025.03.31";
 
+/// Path to a pre-fetched yt-dlp binary, used as is instead of downloading one
+const YTDLP_BINARY_PATH_ENV: &str = "YTDLP_BINARY_PATH";
+/// Directory where verified release binaries are cached across clean builds
+const YTDLP_CACHE_DIR_ENV: &str = "YTDLP_CACHE_DIR";
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Determine the target OS and architecture
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=TARGET");
+    println!("cargo:rerun-if-env-changed={YTDLP_BINARY_PATH_ENV}");
+    println!("cargo:rerun-if-env-changed={YTDLP_CACHE_DIR_ENV}");
+
+    let out_dir = env::var("OUT_DIR")?;
+
+    // generate the rust module file
+    let generated_rs_path = Path::new(&out_dir).join("generated.rs");
+    let mut rust_mod = File::create(&generated_rs_path)?;
+
+    writeln!(
+        rust_mod,
+        "/// The yt-dlp release this crate is pinned to\npub const YTDLP_RELEASE: &str = \"{YTDLP_RELEASE}\";"
+    )?;
+
+    // nothing to vendor, the binary is provided at runtime
+    if env::var_os("CARGO_FEATURE_YT_DLP_VENDORED").is_none() {
+        return Ok(());
+    }
+
+    let binary_path = Path::new(&out_dir).join("yt-dlp");
+    let binary = match env::var_os(YTDLP_BINARY_PATH_ENV) {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            println!("cargo:rerun-if-changed={}", path.display());
+            fs::read(&path)
+                .map_err(|e| format!("Failed to read {YTDLP_BINARY_PATH_ENV} {path:?}: {e}"))?
+        }
+        None => fetch_release_binary(release_filename()?)?,
+    };
+    fs::write(&binary_path, binary)?;
+
+    // write the include_bytes! with relative path from OUT_DIR
+    writeln!(
+        rust_mod,
+        "pub const YTDLP_BINARY: &[u8] = include_bytes!(\"yt-dlp\");"
+    )?;
+
+    Ok(())
+}
+
+/// Determine the release asset for the target OS and architecture
+fn release_filename() -> Result<&'static str, Box<dyn std::error::Error>> {
     let target_os = env::var("CARGO_CFG_TARGET_OS")?;
     let target_arch = env::var("CARGO_CFG_TARGET_ARCH")?;
 
@@ -22,26 +72,87 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         _ => return Err(format!("Unsupported platform: {target_os} {target_arch}").into()),
     };
 
-    let out_dir = env::var("OUT_DIR")?;
-    let binary_path = Path::new(&out_dir).join("yt-dlp");
+    Ok(filename)
+}
+
+/// Returns the verified release binary, reading from `YTDLP_CACHE_DIR` when possible and
+/// falling back to downloading it from github.
+fn fetch_release_binary(filename: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let cache_dir = env::var_os(YTDLP_CACHE_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(YTDLP_RELEASE));
+
+    if let Some(cache_dir) = &cache_dir {
+        let cached_binary = cache_dir.join(filename);
+        let cached_sums = cache_dir.join("SHA2-256SUMS");
 
-    let mut response = reqwest::blocking::get(format!(
+        if let (Ok(binary), Ok(sums)) = (fs::read(&cached_binary), fs::read_to_string(cached_sums))
+        {
+            match verify_checksum(filename, &binary, &sums) {
+                Ok(()) => return Ok(binary),
+                Err(e) => println!("cargo:warning=Ignoring cached yt-dlp binary: {e}"),
+            }
+        }
+    }
+
+    let sums = download(&format!(
+        "https://github.com/yt-dlp/yt-dlp/releases/download/{YTDLP_RELEASE}/SHA2-256SUMS"
+    ))?;
+    let sums = String::from_utf8(sums)?;
+    let binary = download(&format!(
         "https://github.com/yt-dlp/yt-dlp/releases/download/{YTDLP_RELEASE}/{filename}"
     ))?;
-    let mut dest = File::create(&binary_path)?;
-    copy(&mut response, &mut dest)?;
+    verify_checksum(filename, &binary, &sums)?;
 
-    // generate the rust module file
-    let generated_rs_path = Path::new(&out_dir).join("generated.rs");
-    let mut rust_mod = File::create(&generated_rs_path)?;
+    if let Some(cache_dir) = &cache_dir {
+        let cached = fs::create_dir_all(cache_dir)
+            .and_then(|_| fs::write(cache_dir.join(filename), &binary))
+            .and_then(|_| fs::write(cache_dir.join("SHA2-256SUMS"), &sums));
+        if let Err(e) = cached {
+            println!("cargo:warning=Failed to cache yt-dlp binary in {cache_dir:?}: {e}");
+        }
+    }
 
-    // write the include_bytes! with relative path from OUT_DIR
-    writeln!(
-        rust_mod,
-        "pub const YTDLP_BINARY: &[u8] = include_bytes!(\"yt-dlp\");"
-    )?;
+    Ok(binary)
+}
+
+fn download(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let response = reqwest::blocking::get(url)
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| {
+            format!(
+                "Failed to download {url}: {e}. To build offline, set {YTDLP_BINARY_PATH_ENV} to a \
+                 pre-fetched binary or {YTDLP_CACHE_DIR_ENV} to a populated cache directory"
+            )
+        })?;
+
+    Ok(response.bytes()?.to_vec())
+}
+
+/// Checks `binary` against its entry in the release's `SHA2-256SUMS` file
+fn verify_checksum(
+    filename: &str,
+    binary: &[u8],
+    sums: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let expected = sums
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, name)| name.trim().trim_start_matches('*') == filename)
+        .map(|(hash, _)| hash.to_ascii_lowercase())
+        .ok_or_else(|| format!("No checksum found for {filename} in SHA2-256SUMS"))?;
+
+    let actual = Sha256::digest(binary)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for {filename} ({YTDLP_RELEASE}): expected {expected}, got {actual}"
+        )
+        .into());
+    }
 
-    println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rerun-if-env-changed=TARGET");
     Ok(())
 }
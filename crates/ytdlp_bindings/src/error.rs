@@ -8,6 +8,20 @@ pub enum YtDlpError {
         status: i32,
         output: String,
     },
+    #[error("Sign in required (bot check): {0}")]
+    SignInRequired(String),
+    #[error("Cookies are expired or no longer valid: {0}")]
+    CookiesExpired(String),
+    #[error("Rate limited (HTTP 429): {0}")]
+    RateLimited(String),
+    #[error("Video unavailable: {0}")]
+    VideoUnavailable(String),
+    #[error("Video is geo-blocked: {0}")]
+    GeoBlocked(String),
+    #[error("Live stream has not finished: {0}")]
+    LiveNotFinished(String),
+    #[error("Requested format is not available: {0}")]
+    FormatNotAvailable(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
     #[error("Failed to locate {0} binary")]
@@ -20,4 +34,171 @@ pub enum YtDlpError {
     InvalidInputPath(String),
     #[error("Unsupported file format: {0}")]
     UnsupportedFormat(String),
+    #[error("Failed to probe media: {0}")]
+    ProbeError(String),
+    #[error("Invalid time range: {0}")]
+    InvalidTimeRange(String),
+    #[error("Audio chunk exceeds the size limit: {0}")]
+    ChunkTooLarge(String),
+}
+
+type FailureVariant = fn(String) -> YtDlpError;
+
+/// Known yt-dlp stderr fragments, checked in order. Cookie failures come before the
+/// generic sign-in prompt since yt-dlp often prints both when the session is stale.
+const YT_DLP_FAILURE_PATTERNS: &[(&str, FailureVariant)] = &[
+    ("cookies are no longer valid", YtDlpError::CookiesExpired),
+    ("cookies have expired", YtDlpError::CookiesExpired),
+    ("sign in to confirm you", YtDlpError::SignInRequired),
+    (
+        "use --cookies-from-browser or --cookies",
+        YtDlpError::SignInRequired,
+    ),
+    ("http error 429", YtDlpError::RateLimited),
+    ("too many requests", YtDlpError::RateLimited),
+    (
+        "not made this video available in your country",
+        YtDlpError::GeoBlocked,
+    ),
+    ("not available in your country", YtDlpError::GeoBlocked),
+    ("this live event will begin in", YtDlpError::LiveNotFinished),
+    ("premieres in", YtDlpError::LiveNotFinished),
+    (
+        "this live stream recording is not available",
+        YtDlpError::LiveNotFinished,
+    ),
+    ("private video", YtDlpError::VideoUnavailable),
+    ("this video is private", YtDlpError::VideoUnavailable),
+    ("video unavailable", YtDlpError::VideoUnavailable),
+    ("this video has been removed", YtDlpError::VideoUnavailable),
+    (
+        "requested format is not available",
+        YtDlpError::FormatNotAvailable,
+    ),
+];
+
+impl YtDlpError {
+    /// Builds an error from a failed yt-dlp invocation, mapping well known stderr
+    /// messages to specific variants and falling back to [`YtDlpError::NonZeroExit`].
+    pub(crate) fn from_yt_dlp_output(command: String, status: i32, output: String) -> Self {
+        let lowercase = output.to_lowercase();
+
+        for (pattern, variant) in YT_DLP_FAILURE_PATTERNS {
+            if lowercase.contains(pattern) {
+                return variant(error_line(&output, pattern));
+            }
+        }
+
+        YtDlpError::NonZeroExit {
+            command,
+            status,
+            output,
+        }
+    }
+
+    /// Returns `true` if retrying the same command may succeed.
+    ///
+    /// Unclassified non-zero exits are treated as transient (network hiccups, throttled
+    /// fragments), as are rate limits. Everything else needs a change on our side, e.g.
+    /// fresh cookies, a different format or skipping the video altogether.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            YtDlpError::NonZeroExit { .. } | YtDlpError::RateLimited(_)
+        )
+    }
+}
+
+/// Picks the output line containing `pattern`, preferring yt-dlp's `ERROR:` line.
+fn error_line(output: &str, pattern: &str) -> String {
+    output
+        .lines()
+        .find(|line| line.starts_with("ERROR:") && line.to_lowercase().contains(pattern))
+        .or_else(|| {
+            output
+                .lines()
+                .find(|line| line.to_lowercase().contains(pattern))
+        })
+        .unwrap_or(output)
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(stderr: &str) -> YtDlpError {
+        YtDlpError::from_yt_dlp_output("yt-dlp".to_string(), 1, stderr.to_string())
+    }
+
+    #[test]
+    fn classifies_bot_check_as_sign_in_required() {
+        let err = classify(
+            "ERROR: [youtube] CEsTRpeOGkg: Sign in to confirm you’re not a bot. Use --cookies-from-browser or --cookies for the authentication.",
+        );
+        assert!(matches!(err, YtDlpError::SignInRequired(_)));
+        assert!(!err.is_transient());
+    }
+
+    #[test]
+    fn classifies_stale_cookies_before_sign_in() {
+        let err = classify(
+            "WARNING: [youtube] The provided YouTube account cookies are no longer valid. They have likely been rotated in the browser as a security measure.\n\
+             ERROR: [youtube] CEsTRpeOGkg: Sign in to confirm you’re not a bot.",
+        );
+        match err {
+            YtDlpError::CookiesExpired(line) => assert!(line.starts_with("WARNING:")),
+            other => panic!("Expected CookiesExpired, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classifies_rate_limit_as_transient() {
+        let err = classify("ERROR: Unable to download webpage: HTTP Error 429: Too Many Requests");
+        assert!(matches!(err, YtDlpError::RateLimited(_)));
+        assert!(err.is_transient());
+    }
+
+    #[test]
+    fn classifies_unavailable_and_private_videos() {
+        assert!(matches!(
+            classify("ERROR: [youtube] abc: Video unavailable"),
+            YtDlpError::VideoUnavailable(_)
+        ));
+        assert!(matches!(
+            classify("ERROR: [youtube] abc: Private video. Sign in if you've been granted access to this video"),
+            YtDlpError::VideoUnavailable(_)
+        ));
+    }
+
+    #[test]
+    fn classifies_geo_blocked_videos() {
+        let err = classify(
+            "ERROR: [youtube] abc: The uploader has not made this video available in your country",
+        );
+        assert!(matches!(err, YtDlpError::GeoBlocked(_)));
+    }
+
+    #[test]
+    fn classifies_upcoming_live_events() {
+        let err = classify("ERROR: [youtube] abc: This live event will begin in 3 hours.");
+        assert!(matches!(err, YtDlpError::LiveNotFinished(_)));
+    }
+
+    #[test]
+    fn classifies_missing_formats() {
+        let err = classify(
+            "ERROR: [youtube] abc: Requested format is not available. Use --list-formats for a list of available formats",
+        );
+        assert!(matches!(err, YtDlpError::FormatNotAvailable(_)));
+        assert!(!err.is_transient());
+    }
+
+    #[test]
+    fn falls_back_to_non_zero_exit() {
+        let err = classify("ERROR: Unable to download webpage: <urlopen error timed out>");
+        assert!(matches!(err, YtDlpError::NonZeroExit { status: 1, .. }));
+        assert!(err.is_transient());
+    }
 }
@@ -7,11 +7,15 @@
 //!
 //! # Features
 //!
-//! - `yt-dlp-vendored`: When enabled, the crate will use a vendored version of yt-dlp.
+//! - `yt-dlp-vendored`: When enabled, the crate will use a vendored version of yt-dlp ([`YTDLP_RELEASE`]).
 //!   When disabled, you need to provide the path to the yt-dlp binary.
-//! - `audio-processing`: Adds downloaded audio processing capabilities to YtDlp via vendored ffmpeg (v7*)
-//! - `video-processing`: Adds downloaded video processing capabilities to YtDlp also via vendored ffmpeg (v7*)
+//! - `audio-processing`: Adds downloaded audio processing capabilities to YtDlp via vendored ffmpeg (v7*),
+//!   along with media inspection via `ffprobe` ([`MediaProber`])
+//! - `video-processing`: Adds downloaded video processing capabilities to YtDlp also via vendored ffmpeg (v7*),
+//!   along with media inspection via `ffprobe` ([`MediaProber`])
 //! - `vtt-processing`: Adds downloaded VTT file processing capabilities to YtDlp
+//! - `test-utils`: Exposes [`FakeCommandRunner`] for testing code built on `YtDlp` without
+//!   spawning real processes (not enabled by default)
 //!
 //! # Examples
 //!
@@ -29,6 +33,7 @@
 //! }
 //! ```
 
+mod duration_secs;
 mod error;
 #[cfg(any(
     feature = "audio-processing",
@@ -36,15 +41,31 @@ mod error;
     feature = "vtt-processing"
 ))]
 mod processors;
+mod runner;
+mod subtitles;
+mod time_map;
+mod time_range;
+mod transcript;
 mod ytldp;
 
 pub use error::YtDlpError;
 #[cfg(feature = "audio-processing")]
-pub use processors::audio::AudioProcessor;
+pub use processors::audio::{AudioChunk, AudioProcessor, ChunkLimits, Silence};
+#[cfg(feature = "audio-processing")]
+pub use processors::audio_config::{AudioCodec, AudioProcessingConfig, LoudnessTarget};
+#[cfg(any(feature = "audio-processing", feature = "video-processing"))]
+pub use processors::probe::{LoudnessStats, MediaInfo, MediaProber, MediaStream};
 #[cfg(feature = "video-processing")]
-pub use processors::video::VideoProcessor;
+pub use processors::video::{FrameOptions, ImageFormat, VideoProcessor};
 #[cfg(feature = "vtt-processing")]
 pub use processors::vtt::VttProcessor;
+pub use runner::{CommandOutput, CommandRunner, SystemCommandRunner};
+#[cfg(feature = "test-utils")]
+pub use runner::{FakeCommandRunner, RecordedCommand, ScriptedResponse};
+pub use subtitles::{SubtitleFormat, SubtitleWriter};
+pub use time_map::TimeMap;
+pub use time_range::TimeRange;
+pub use transcript::TranscriptSegment;
 #[cfg(feature = "vtt-processing")]
 pub use webvtt_parser::{OwnedVtt, OwnedVttCue};
-pub use ytldp::YtDlp;
+pub use ytldp::{YtDlp, YTDLP_RELEASE};
@@ -3,26 +3,139 @@
 //! Enrich `YtDlp` by adding audio processing capabilities such as
 //! denoising, volume normalization, silence trimming, and chunking.
 
-use std::path::Path;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use crate::{YtDlp, YtDlpError};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    time_range::parse_timestamp, AudioProcessingConfig, MediaInfo, MediaProber, TimeMap, TimeRange,
+    YtDlp, YtDlpError,
+};
+
+/// Silence kept on either side of a removed internal pause, so speech isn't clipped
+const REMOVED_SILENCE_PADDING: Duration = Duration::from_millis(500);
+
+/// Name of the segment list ffmpeg writes beside the chunks while splitting
+const SEGMENT_LIST_FILE: &str = ".segments.csv";
+
+/// A chunk produced by splitting an audio file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioChunk {
+    /// Position of the chunk, starting at 0
+    pub index: usize,
+    pub path: PathBuf,
+    /// Offset of the chunk's start in the audio that was split
+    #[serde(with = "crate::duration_secs")]
+    pub start: Duration,
+    /// Offset of the chunk's end in the audio that was split
+    #[serde(with = "crate::duration_secs")]
+    pub end: Duration,
+    /// File size in bytes
+    pub bytes: u64,
+}
+
+impl AudioChunk {
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Writes `chunks` as a JSON manifest, typically beside the chunks themselves.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the manifest cannot be serialized or written.
+    pub fn write_manifest(chunks: &[AudioChunk], path: impl AsRef<Path>) -> Result<(), YtDlpError> {
+        fs::write(path, serde_json::to_vec_pretty(chunks)?)?;
+        Ok(())
+    }
+
+    /// Reads a manifest written by [`AudioChunk::write_manifest`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the manifest cannot be read or parsed.
+    pub fn read_manifest(path: impl AsRef<Path>) -> Result<Vec<AudioChunk>, YtDlpError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+}
+
+/// A pause in an audio file as reported by ffmpeg's `silencedetect` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Silence {
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl Silence {
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn midpoint(&self) -> Duration {
+        self.start + self.duration() / 2
+    }
+}
+
+/// Limits on the chunks produced by [`AudioProcessor::split_audio_to_limited_chunks`].
+///
+/// Built from either limit, optionally adding the other, e.g.
+/// `ChunkLimits::max_bytes(25 * 1024 * 1024).with_max_duration(Duration::from_secs(900))`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLimits {
+    max_duration: Option<Duration>,
+    max_bytes: Option<u64>,
+}
+
+impl ChunkLimits {
+    pub fn max_duration(max_duration: Duration) -> Self {
+        ChunkLimits {
+            max_duration: Some(max_duration),
+            max_bytes: None,
+        }
+    }
+
+    pub fn max_bytes(max_bytes: u64) -> Self {
+        ChunkLimits {
+            max_duration: None,
+            max_bytes: Some(max_bytes),
+        }
+    }
+
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+}
 
 /// A trait for processing audio files using `ffmpeg`.
 /// Requires `ffmpeg` v7+ available in the environment.
 pub trait AudioProcessor {
     /// Split an audio file into fixed-length chunks (in seconds).
+    ///
+    /// Returns the chunks in order, with their offsets in the input.
     fn split_audio_to_chunks(
         &self,
         file_input_path: impl AsRef<Path>,
         segment_time_s: u16,
-        out_template: impl AsRef<Path>,
-    ) -> Result<(), YtDlpError>;
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError>;
 
     /// Normalize volume using EBU R128 loudness standard.
     fn normalize_volume(
         &self,
         input_path: impl AsRef<Path>,
         output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
     ) -> Result<(), YtDlpError>;
 
     /// Apply basic denoising filter (FFT-based).
@@ -30,6 +143,7 @@ pub trait AudioProcessor {
         &self,
         input_path: impl AsRef<Path>,
         output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
     ) -> Result<(), YtDlpError>;
 
     /// Trim leading and trailing silence.
@@ -37,144 +151,1479 @@ pub trait AudioProcessor {
         &self,
         input_path: impl AsRef<Path>,
         output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
     ) -> Result<(), YtDlpError>;
-}
 
-impl AudioProcessor for YtDlp {
-    fn split_audio_to_chunks(
+    /// Remove silences (suspensions, dead air...) longer than `min_silence` throughout the
+    /// audio, along with any leading and trailing silence.
+    ///
+    /// Returns a [`TimeMap`] converting positions in the output back to positions in the input.
+    fn remove_long_silences(
+        &self,
+        input_path: impl AsRef<Path>,
+        min_silence: Duration,
+        output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<TimeMap, YtDlpError>;
+
+    /// Denoise, normalize volume, trim silence and split into fixed-length chunks (in seconds)
+    /// in a single ffmpeg pass.
+    ///
+    /// Equivalent to chaining [`AudioProcessor::denoise_audio`], [`AudioProcessor::normalize_volume`],
+    /// [`AudioProcessor::trim_silence`] and [`AudioProcessor::split_audio_to_chunks`], but decodes
+    /// and encodes the input only once and writes no intermediate files.
+    ///
+    /// Chunk offsets are relative to the conditioned audio, which starts after any trimmed
+    /// leading silence, see [`AudioProcessingConfig::trim_leading_silence`].
+    fn condition_and_segment(
         &self,
         file_input_path: impl AsRef<Path>,
         segment_time_s: u16,
         output_template: impl AsRef<Path>,
-    ) -> Result<(), YtDlpError> {
-        let input_path = file_input_path.as_ref();
-        let output_path = output_template.as_ref();
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError>;
 
-        let input_str = input_path
-            .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(input_path.display().to_string()))?;
-        let output_str = output_path
-            .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(output_path.display().to_string()))?;
+    /// Detect pauses in an audio file using ffmpeg's `silencedetect` filter.
+    ///
+    /// See [`AudioProcessingConfig::pause_threshold_db`] and [`AudioProcessingConfig::min_pause`].
+    fn detect_silences(
+        &self,
+        input_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<Silence>, YtDlpError>;
 
-        let ext = output_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_ascii_lowercase();
-
-        let codec = match ext.as_str() {
-            "wav" => "pcm_s16le",
-            "mp3" => "libmp3lame",
-            "flac" => "flac",
-            "aac" => "aac",
-            _ => return Err(YtDlpError::UnsupportedFormat(ext)),
-        };
+    /// Split an audio file into chunks of roughly `target_segment_s` seconds, placing each cut
+    /// at the pause nearest to the target within `tolerance_s` seconds either side of it.
+    /// Falls back to a hard cut at the target when there is no pause in that window.
+    ///
+    /// Returns the chunks in order. Every chunk but the first starts at a cut point.
+    fn split_audio_at_silences(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        target_segment_s: u16,
+        tolerance_s: u16,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError>;
+
+    /// Same as [`AudioProcessor::condition_and_segment`], with cuts placed at pauses like
+    /// [`AudioProcessor::split_audio_at_silences`].
+    ///
+    /// Pauses are detected on the conditioned audio, so this decodes the input twice but still
+    /// encodes it only once. Chunk offsets are relative to the conditioned audio.
+    fn condition_and_segment_at_silences(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        target_segment_s: u16,
+        tolerance_s: u16,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError>;
 
-        self.run_ffmpeg(&[
-            "-i",
-            input_str,
-            "-f",
-            "segment",
-            "-segment_time",
-            &segment_time_s.to_string(),
-            "-ac",
-            "1",
-            "-ar",
-            "16000",
-            "-c:a",
-            codec,
-            output_str,
-        ])
+    /// Split an audio file into chunks that respect both a maximum duration and a maximum
+    /// file size, e.g. the upload limit of a transcription API.
+    ///
+    /// The segment length for the size limit is estimated from the input's probed bitrate.
+    /// Chunks that still turn out too large are re-split, see [`AudioProcessor::fit_chunks_to_size`].
+    fn split_audio_to_limited_chunks(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        limits: ChunkLimits,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError>;
+
+    /// Re-split every chunk larger than `max_bytes` into evenly sized pieces, replacing it on disk.
+    ///
+    /// Cuts are moved to nearby pauses and the pieces are copied from the chunk rather than
+    /// re-encoded, so `config` only sets how pauses are detected. Pieces are written beside the
+    /// chunk they came from and keep offsets relative to the original audio, and the returned
+    /// chunks are re-indexed in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`YtDlpError::ChunkTooLarge`] if even a one second piece exceeds `max_bytes`.
+    fn fit_chunks_to_size(
+        &self,
+        chunks: Vec<AudioChunk>,
+        max_bytes: u64,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError>;
+
+    /// Cut `range` out of the input into a standalone clip, e.g. to share a quote.
+    ///
+    /// The clip fades in and out over `fade`, at most half the clip each way. Without a fade,
+    /// and if the output codec matches the input's, the audio is copied as is rather than
+    /// re-encoded, in which case `config` is not applied. Ranges running past the end of the
+    /// input are cut short.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`YtDlpError::InvalidTimeRange`] if `range` starts past the end of the input.
+    fn extract_clip(
+        &self,
+        input_path: impl AsRef<Path>,
+        range: TimeRange,
+        fade: Duration,
+        output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<(), YtDlpError>;
+}
+
+impl AudioProcessor for YtDlp {
+    fn split_audio_to_chunks(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        segment_time_s: u16,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError> {
+        segment(
+            self,
+            file_input_path.as_ref(),
+            None,
+            SegmentBoundaries::Every(segment_time_s),
+            output_template.as_ref(),
+            ChunkEncoding::Encode(config),
+        )
     }
 
     fn normalize_volume(
         &self,
         input_path: impl AsRef<Path>,
         output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
     ) -> Result<(), YtDlpError> {
-        let input_str = input_path
-            .as_ref()
-            .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(input_path.as_ref().display().to_string()))?;
-        let output_str = output_path
-            .as_ref()
-            .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(output_path.as_ref().display().to_string()))?;
-
-        let codec = infer_codec(output_path.as_ref())?;
+        // fail before a measurement pass rather than after it
+        config.codec_for(output_path.as_ref())?;
+        let filter = config.resolve_normalize_filter(self, input_path.as_ref(), None)?;
 
-        self.run_ffmpeg(&[
-            "-i", input_str, "-af", "loudnorm", "-ar", "16000", "-ac", "1", "-c:a", codec,
-            output_str,
-        ])
+        encode_with_filter(
+            self,
+            input_path.as_ref(),
+            &filter,
+            output_path.as_ref(),
+            config,
+        )
     }
 
     fn denoise_audio(
         &self,
         input_path: impl AsRef<Path>,
         output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
     ) -> Result<(), YtDlpError> {
-        let input_str = input_path
-            .as_ref()
-            .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(input_path.as_ref().display().to_string()))?;
-        let output_str = output_path
-            .as_ref()
-            .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(output_path.as_ref().display().to_string()))?;
+        encode_with_filter(
+            self,
+            input_path.as_ref(),
+            &config.denoise_filter(),
+            output_path.as_ref(),
+            config,
+        )
+    }
 
-        let codec = infer_codec(output_path.as_ref())?;
+    fn trim_silence(
+        &self,
+        input_path: impl AsRef<Path>,
+        output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<(), YtDlpError> {
+        encode_with_filter(
+            self,
+            input_path.as_ref(),
+            &config.trim_silence_filter(),
+            output_path.as_ref(),
+            config,
+        )
+    }
 
-        self.run_ffmpeg(&[
-            "-i", input_str, "-af", "afftdn", "-ar", "16000", "-ac", "1", "-c:a", codec, output_str,
-        ])
+    fn remove_long_silences(
+        &self,
+        input_path: impl AsRef<Path>,
+        min_silence: Duration,
+        output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<TimeMap, YtDlpError> {
+        config.codec_for(output_path.as_ref())?;
+
+        let duration = self.probe(input_path.as_ref())?.duration;
+        let silences = self.detect_silences(input_path.as_ref(), config)?;
+        let time_map =
+            TimeMap::from_removed(duration, &removable_spans(duration, &silences, min_silence));
+
+        encode_with_filter(
+            self,
+            input_path.as_ref(),
+            &remove_spans_filter(&time_map.removed_spans()),
+            output_path.as_ref(),
+            config,
+        )?;
+
+        Ok(time_map)
     }
 
-    fn trim_silence(
+    fn condition_and_segment(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        segment_time_s: u16,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError> {
+        config.codec_for(output_template.as_ref())?;
+        let filter_graph = conditioning_filters(self, file_input_path.as_ref(), config)?;
+
+        segment(
+            self,
+            file_input_path.as_ref(),
+            Some(&filter_graph),
+            SegmentBoundaries::Every(segment_time_s),
+            output_template.as_ref(),
+            ChunkEncoding::Encode(config),
+        )
+    }
+
+    fn detect_silences(
         &self,
         input_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<Silence>, YtDlpError> {
+        let (silences, _) = detect_silences_with_filters(self, input_path.as_ref(), None, config)?;
+        Ok(silences)
+    }
+
+    fn split_audio_at_silences(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        target_segment_s: u16,
+        tolerance_s: u16,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError> {
+        segment_at_silences(
+            self,
+            file_input_path.as_ref(),
+            None,
+            target_segment_s,
+            tolerance_s,
+            output_template.as_ref(),
+            config,
+        )
+    }
+
+    fn condition_and_segment_at_silences(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        target_segment_s: u16,
+        tolerance_s: u16,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError> {
+        config.codec_for(output_template.as_ref())?;
+        let filter_graph = conditioning_filters(self, file_input_path.as_ref(), config)?;
+
+        segment_at_silences(
+            self,
+            file_input_path.as_ref(),
+            Some(&filter_graph),
+            target_segment_s,
+            tolerance_s,
+            output_template.as_ref(),
+            config,
+        )
+    }
+
+    fn split_audio_to_limited_chunks(
+        &self,
+        file_input_path: impl AsRef<Path>,
+        limits: ChunkLimits,
+        output_template: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError> {
+        let input_path = file_input_path.as_ref();
+        let output_path = output_template.as_ref();
+        let codec = config.codec_for(output_path)?;
+
+        let size_limited = match limits.max_bytes {
+            Some(max_bytes) => {
+                let bit_rate = match config.output_bit_rate(codec) {
+                    Some(bit_rate) => bit_rate,
+                    None => probed_bit_rate(&self.probe(input_path)?)?,
+                };
+                Some(Duration::from_secs_f64(
+                    max_bytes as f64 * 8.0 * SIZE_HEADROOM / bit_rate as f64,
+                ))
+            }
+            None => None,
+        };
+
+        let segment_time = [limits.max_duration, size_limited]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(Duration::from_secs(u16::MAX.into()));
+        let segment_time_s = segment_time.as_secs().clamp(1, u16::MAX.into()) as u16;
+
+        let chunks = segment(
+            self,
+            input_path,
+            None,
+            SegmentBoundaries::Every(segment_time_s),
+            output_path,
+            ChunkEncoding::Encode(config),
+        )?;
+
+        match limits.max_bytes {
+            Some(max_bytes) => self.fit_chunks_to_size(chunks, max_bytes, config),
+            None => Ok(chunks),
+        }
+    }
+
+    fn fit_chunks_to_size(
+        &self,
+        chunks: Vec<AudioChunk>,
+        max_bytes: u64,
+        config: &AudioProcessingConfig,
+    ) -> Result<Vec<AudioChunk>, YtDlpError> {
+        let mut fitted = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            fit_chunk_to_size(self, chunk, max_bytes, config, &mut fitted)?;
+        }
+
+        for (index, chunk) in fitted.iter_mut().enumerate() {
+            chunk.index = index;
+        }
+
+        Ok(fitted)
+    }
+
+    fn extract_clip(
+        &self,
+        input_path: impl AsRef<Path>,
+        range: TimeRange,
+        fade: Duration,
         output_path: impl AsRef<Path>,
+        config: &AudioProcessingConfig,
     ) -> Result<(), YtDlpError> {
+        let input_path = input_path.as_ref();
+        let output_path = output_path.as_ref();
         let input_str = input_path
-            .as_ref()
             .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(input_path.as_ref().display().to_string()))?;
+            .ok_or_else(|| YtDlpError::InvalidPath(input_path.display().to_string()))?;
         let output_str = output_path
-            .as_ref()
             .to_str()
-            .ok_or_else(|| YtDlpError::InvalidPath(output_path.as_ref().display().to_string()))?;
+            .ok_or_else(|| YtDlpError::InvalidPath(output_path.display().to_string()))?;
+        let codec = config.codec_for(output_path)?;
 
-        let codec = infer_codec(output_path.as_ref())?;
+        let info = self.probe(input_path)?;
+        if range.start() >= info.duration {
+            return Err(YtDlpError::InvalidTimeRange(format!(
+                "clip {range} starts past the end of {} ({:.3}s)",
+                input_path.display(),
+                info.duration.as_secs_f64()
+            )));
+        }
+        let duration = range.end().min(info.duration) - range.start();
+        let fade = fade.min(duration / 2);
+        let input_codec = info
+            .audio_stream()
+            .and_then(|stream| stream.codec_name.as_deref());
 
-        self.run_ffmpeg(&[
-            "-i",
-            input_str,
-            "-af",
-            "silenceremove=start_periods=1:start_threshold=-50dB:start_silence=0.1",
-            "-ar",
-            "16000",
-            "-ac",
-            "1",
-            "-c:a",
-            codec,
-            output_str,
-        ])
+        let start = format!("{:.3}", range.start().as_secs_f64());
+        let length = format!("{:.3}", duration.as_secs_f64());
+        let fade_filter = format!(
+            "afade=t=in:d={fade:.3},afade=t=out:st={:.3}:d={fade:.3}",
+            (duration - fade).as_secs_f64(),
+            fade = fade.as_secs_f64()
+        );
+        let encoding_args = config.encoding_args(codec);
+
+        // seeking before the input resets timestamps, so fades are relative to the clip
+        let mut args = vec!["-ss", &start, "-t", &length, "-i", input_str];
+        if fade.is_zero() && input_codec == Some(codec.probe_name()) {
+            args.extend(["-c:a", "copy"]);
+        } else {
+            if !fade.is_zero() {
+                args.extend(["-af", &fade_filter]);
+            }
+            args.extend(encoding_args.iter().map(String::as_str));
+        }
+        args.push(output_str);
+
+        // ffmpeg won't overwrite a clip left by an earlier run
+        let _ = std::fs::remove_file(output_path);
+        self.run_ffmpeg(&args)
     }
 }
 
-fn infer_codec(path: &Path) -> Result<&'static str, YtDlpError> {
-    match path
+/// Encodes `input_path` through `filter` into `output_path`
+fn encode_with_filter(
+    ytdlp: &YtDlp,
+    input_path: &Path,
+    filter: &str,
+    output_path: &Path,
+    config: &AudioProcessingConfig,
+) -> Result<(), YtDlpError> {
+    let input_str = input_path
+        .to_str()
+        .ok_or_else(|| YtDlpError::InvalidPath(input_path.display().to_string()))?;
+    let output_str = output_path
+        .to_str()
+        .ok_or_else(|| YtDlpError::InvalidPath(output_path.display().to_string()))?;
+
+    let encoding_args = config.encoding_args(config.codec_for(output_path)?);
+
+    let mut args = vec!["-i", input_str, "-af", filter];
+    args.extend(encoding_args.iter().map(String::as_str));
+    args.push(output_str);
+
+    ytdlp.run_ffmpeg(&args)
+}
+
+/// Denoise, normalize and trim leading silence unless turned off, measuring loudness first for
+/// two-pass normalization
+fn conditioning_filters(
+    ytdlp: &YtDlp,
+    input_path: &Path,
+    config: &AudioProcessingConfig,
+) -> Result<String, YtDlpError> {
+    let denoise = config.denoise_filter();
+    let normalize = config.resolve_normalize_filter(ytdlp, input_path, Some(&denoise))?;
+
+    let mut filters = vec![denoise, normalize];
+    if config.trim_leading_silence {
+        filters.push(config.trim_silence_filter());
+    }
+
+    Ok(filters.join(","))
+}
+
+/// Share of a size limit targeted when estimating segment lengths, leaving room for
+/// container overhead and bitrate variations
+const SIZE_HEADROOM: f64 = 0.9;
+
+/// Bitrate (bits per second) of the probed input's audio
+fn probed_bit_rate(info: &MediaInfo) -> Result<u64, YtDlpError> {
+    info.audio_stream()
+        .and_then(|s| s.bit_rate)
+        .or(info.bit_rate)
+        .filter(|bit_rate| *bit_rate > 0)
+        .ok_or_else(|| {
+            YtDlpError::ProbeError(format!(
+                "No bitrate reported for '{}' input, can't estimate chunk sizes",
+                info.format_name
+            ))
+        })
+}
+
+/// Appends `chunk` to `fitted`, splitting it into smaller pieces first if it exceeds `max_bytes`
+fn fit_chunk_to_size(
+    ytdlp: &YtDlp,
+    chunk: AudioChunk,
+    max_bytes: u64,
+    config: &AudioProcessingConfig,
+    fitted: &mut Vec<AudioChunk>,
+) -> Result<(), YtDlpError> {
+    if chunk.bytes <= max_bytes {
+        fitted.push(chunk);
+        return Ok(());
+    }
+
+    let too_large = || {
+        YtDlpError::ChunkTooLarge(format!(
+            "{} is {} bytes, limit is {max_bytes}",
+            chunk.path.display(),
+            chunk.bytes
+        ))
+    };
+
+    let pieces = (chunk.bytes as f64 / (max_bytes as f64 * SIZE_HEADROOM)).ceil();
+    let piece_secs = (chunk.duration().as_secs_f64() / pieces).floor();
+    if piece_secs < 1.0 {
+        return Err(too_large());
+    }
+
+    let stem = chunk
+        .path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| YtDlpError::InvalidPath(chunk.path.display().to_string()))?;
+    let ext = chunk
+        .path
         .extension()
         .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_ascii_lowercase()
-        .as_str()
-    {
-        "wav" => Ok("pcm_s16le"),
-        "mp3" => Ok("libmp3lame"),
-        "flac" => Ok("flac"),
-        "aac" => Ok("aac"),
-        ext => Err(YtDlpError::UnsupportedFormat(ext.to_string())),
+        .ok_or_else(|| YtDlpError::UnsupportedFormat(chunk.path.display().to_string()))?;
+    let piece_template = chunk.path.with_file_name(format!("{stem}_%03d.{ext}"));
+
+    // the chunk is already encoded, so it's cut at pauses and copied rather than re-encoded,
+    // moving cuts by at most a tenth of a piece, about what SIZE_HEADROOM leaves room for
+    let target = Duration::from_secs_f64(piece_secs);
+    let (silences, processed) = detect_silences_with_filters(ytdlp, &chunk.path, None, config)?;
+    let duration = processed.unwrap_or_else(|| chunk.duration());
+    let mut cut_points = choose_cut_points(duration, &silences, target, target / 10);
+    if cut_points.is_empty() {
+        cut_points.push(duration);
+    }
+
+    let pieces = segment(
+        ytdlp,
+        &chunk.path,
+        None,
+        SegmentBoundaries::At(cut_points),
+        &piece_template,
+        ChunkEncoding::Copy,
+    )?;
+    // a single piece means the chunk couldn't be split any further
+    if pieces.len() < 2 {
+        for piece in &pieces {
+            let _ = fs::remove_file(&piece.path);
+        }
+        return Err(too_large());
+    }
+    fs::remove_file(&chunk.path)?;
+
+    for mut piece in pieces {
+        piece.start += chunk.start;
+        piece.end = (piece.end + chunk.start).min(chunk.end);
+        fit_chunk_to_size(ytdlp, piece, max_bytes, config, fitted)?;
+    }
+
+    Ok(())
+}
+
+/// Spans of `silences` worth cutting out: every silence touching the start or end of the audio,
+/// and internal ones of at least `min_silence`, minus some padding on the side of the speech.
+fn removable_spans(
+    duration: Duration,
+    silences: &[Silence],
+    min_silence: Duration,
+) -> Vec<(Duration, Duration)> {
+    // silencedetect reports times with limited precision
+    let edge_tolerance = Duration::from_millis(50);
+
+    silences
+        .iter()
+        .filter_map(|silence| {
+            let leading = silence.start <= edge_tolerance;
+            let trailing = silence.end + edge_tolerance >= duration;
+
+            if !leading && !trailing && silence.duration() < min_silence {
+                return None;
+            }
+
+            let start = if leading {
+                Duration::ZERO
+            } else {
+                silence.start + REMOVED_SILENCE_PADDING
+            };
+            let end = if trailing {
+                duration
+            } else {
+                silence.end.saturating_sub(REMOVED_SILENCE_PADDING)
+            };
+
+            (start < end).then_some((start, end))
+        })
+        .collect()
+}
+
+/// Builds a filter dropping the given `(start, end)` spans and closing the gaps they leave.
+fn remove_spans_filter(spans: &[(Duration, Duration)]) -> String {
+    if spans.is_empty() {
+        return "anull".to_string();
+    }
+
+    let between = spans
+        .iter()
+        .map(|(start, end)| {
+            format!(
+                "between(t,{:.3},{:.3})",
+                start.as_secs_f64(),
+                end.as_secs_f64()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("+");
+
+    // quoted so that the commas within the expression aren't read as filter separators
+    format!("aselect='not({between})',asetpts=N/SR/TB")
+}
+
+/// Runs `silencedetect` after `filters`, so that the reported pauses line up with the
+/// timeline of the filtered audio, which is also what the returned duration is of, when
+/// ffmpeg reports it.
+fn detect_silences_with_filters(
+    ytdlp: &YtDlp,
+    input_path: &Path,
+    filters: Option<&str>,
+    config: &AudioProcessingConfig,
+) -> Result<(Vec<Silence>, Option<Duration>), YtDlpError> {
+    let input_str = input_path
+        .to_str()
+        .ok_or_else(|| YtDlpError::InvalidPath(input_path.display().to_string()))?;
+
+    let detect_filter = config.detect_silence_filter();
+    let filter_graph = match filters {
+        Some(filters) => format!("{filters},{detect_filter}"),
+        None => detect_filter,
+    };
+
+    // -nostats still leaves the final progress report, with the length of the filtered audio
+    let stderr = ytdlp.run_ffmpeg_with_output(&[
+        "-nostats",
+        "-i",
+        input_str,
+        "-af",
+        &filter_graph,
+        "-f",
+        "null",
+        "-",
+    ])?;
+
+    Ok((
+        parse_silencedetect_output(&stderr),
+        parse_processed_duration(&stderr),
+    ))
+}
+
+fn segment_at_silences(
+    ytdlp: &YtDlp,
+    input_path: &Path,
+    filters: Option<&str>,
+    target_segment_s: u16,
+    tolerance_s: u16,
+    output_path: &Path,
+    config: &AudioProcessingConfig,
+) -> Result<Vec<AudioChunk>, YtDlpError> {
+    // fail before the expensive passes rather than after them
+    config.codec_for(output_path)?;
+
+    // filters can shorten the audio, e.g. by trimming leading silence, and the cuts are placed
+    // on the filtered timeline
+    let (silences, processed) = detect_silences_with_filters(ytdlp, input_path, filters, config)?;
+    let duration = match processed {
+        Some(duration) => duration,
+        None => ytdlp.probe(input_path)?.duration,
+    };
+    let mut cut_points = choose_cut_points(
+        duration,
+        &silences,
+        Duration::from_secs(target_segment_s.into()),
+        Duration::from_secs(tolerance_s.into()),
+    );
+
+    // the segment muxer needs at least one time, cutting at the very end yields a single chunk
+    if cut_points.is_empty() {
+        cut_points.push(duration);
+    }
+
+    segment(
+        ytdlp,
+        input_path,
+        filters,
+        SegmentBoundaries::At(cut_points),
+        output_path,
+        ChunkEncoding::Encode(config),
+    )
+}
+
+/// Where the segment muxer cuts
+enum SegmentBoundaries {
+    /// Every given number of seconds
+    Every(u16),
+    /// At the given offsets
+    At(Vec<Duration>),
+}
+
+/// How the segment muxer writes the chunks
+enum ChunkEncoding<'a> {
+    /// Encoded as configured
+    Encode(&'a AudioProcessingConfig),
+    /// Copied from the input as is, for inputs already encoded as the chunks should be
+    Copy,
+}
+
+/// Runs the segment muxer over `input_path`, optionally through `filters`, and collects the
+/// resulting chunks from the segment list ffmpeg writes alongside them.
+fn segment(
+    ytdlp: &YtDlp,
+    input_path: &Path,
+    filters: Option<&str>,
+    boundaries: SegmentBoundaries,
+    output_path: &Path,
+    encoding: ChunkEncoding<'_>,
+) -> Result<Vec<AudioChunk>, YtDlpError> {
+    let input_str = input_path
+        .to_str()
+        .ok_or_else(|| YtDlpError::InvalidPath(input_path.display().to_string()))?;
+    let output_str = output_path
+        .to_str()
+        .ok_or_else(|| YtDlpError::InvalidPath(output_path.display().to_string()))?;
+
+    let encoding_args = match encoding {
+        ChunkEncoding::Encode(config) => config.encoding_args(config.codec_for(output_path)?),
+        ChunkEncoding::Copy => vec!["-c:a".to_string(), "copy".to_string()],
+    };
+
+    let output_dir = output_path.parent().unwrap_or(Path::new(""));
+    let segment_list_path = output_dir.join(SEGMENT_LIST_FILE);
+    let segment_list_str = segment_list_path
+        .to_str()
+        .ok_or_else(|| YtDlpError::InvalidPath(segment_list_path.display().to_string()))?;
+
+    let (segment_flag, segment_value) = match boundaries {
+        SegmentBoundaries::Every(secs) => ("-segment_time", secs.to_string()),
+        SegmentBoundaries::At(cut_points) => (
+            "-segment_times",
+            cut_points
+                .iter()
+                .map(|cut| format!("{:.3}", cut.as_secs_f64()))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    };
+
+    let mut args = vec!["-i", input_str];
+    if let Some(filters) = filters {
+        args.extend(["-af", filters]);
+    }
+    args.extend([
+        "-f",
+        "segment",
+        segment_flag,
+        &segment_value,
+        "-segment_list",
+        segment_list_str,
+        "-segment_list_type",
+        "csv",
+    ]);
+    args.extend(encoding_args.iter().map(String::as_str));
+    args.push(output_str);
+
+    ytdlp.run_ffmpeg(&args)?;
+
+    let segment_list = fs::read_to_string(&segment_list_path)?;
+    let _ = fs::remove_file(&segment_list_path);
+
+    parse_segment_list(&segment_list)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, (file_name, start, end))| {
+            let path = output_dir.join(file_name);
+            let bytes = fs::metadata(&path)?.len();
+
+            Ok(AudioChunk {
+                index,
+                path,
+                start,
+                end,
+                bytes,
+            })
+        })
+        .collect()
+}
+
+/// Parses the segment muxer's CSV list, one `file name,start,end` line per chunk:
+///
+/// ```text
+/// p40gmygQL2c_000.mp3,0.000000,901.420000
+/// p40gmygQL2c_001.mp3,901.420000,1795.500000
+/// ```
+fn parse_segment_list(segment_list: &str) -> Result<Vec<(&str, Duration, Duration)>, YtDlpError> {
+    segment_list
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let malformed =
+                || YtDlpError::ProbeError(format!("Malformed segment list entry: {line}"));
+            let seconds = |value: &str| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs.max(0.0)).ok())
+                    .ok_or_else(malformed)
+            };
+
+            // file names may contain commas, the times never do
+            let mut fields = line.rsplitn(3, ',');
+            let end = seconds(fields.next().ok_or_else(malformed)?)?;
+            let start = seconds(fields.next().ok_or_else(malformed)?)?;
+            let file_name = fields.next().ok_or_else(malformed)?.trim_matches('"');
+
+            Ok((file_name, start, end))
+        })
+        .collect()
+}
+
+/// Picks a cut roughly every `target` until the end of the audio, moving each one to the
+/// closest pause within `tolerance` of the target and making a hard cut when there is none.
+///
+/// A pause spanning the target is cut at the target itself, otherwise at the pause's
+/// midpoint, or its nearest edge when the midpoint falls outside the window.
+fn choose_cut_points(
+    duration: Duration,
+    silences: &[Silence],
+    target: Duration,
+    tolerance: Duration,
+) -> Vec<Duration> {
+    let mut cut_points = Vec::new();
+    if target.is_zero() {
+        return cut_points;
+    }
+
+    let mut chunk_start = Duration::ZERO;
+    loop {
+        let target_cut = chunk_start + target;
+        if target_cut >= duration {
+            break;
+        }
+
+        // never move a cut back onto or before the previous one
+        let window_start = target_cut
+            .saturating_sub(tolerance)
+            .max(chunk_start + Duration::from_millis(1));
+        let window_end = (target_cut + tolerance).min(duration);
+        let in_window = |point: Duration| point >= window_start && point <= window_end;
+
+        let cut = silences
+            .iter()
+            .filter_map(|silence| {
+                let midpoint = silence.midpoint();
+                let nearest = target_cut.clamp(silence.start, silence.end.max(silence.start));
+                if silence.start <= target_cut && target_cut <= silence.end {
+                    Some(target_cut)
+                } else if in_window(midpoint) {
+                    Some(midpoint)
+                } else if in_window(nearest) {
+                    Some(nearest)
+                } else {
+                    None
+                }
+            })
+            .min_by_key(|point| point.abs_diff(target_cut))
+            .unwrap_or(target_cut);
+
+        cut_points.push(cut);
+        chunk_start = cut;
+    }
+
+    cut_points
+}
+
+/// Parses `silencedetect` events from ffmpeg's stderr:
+///
+/// ```text
+/// [silencedetect @ 0x...] silence_start: 901.42
+/// [silencedetect @ 0x...] silence_end: 903.1 | silence_duration: 1.68
+/// ```
+///
+/// A pause still open when the input ends has no `silence_end` and is dropped.
+fn parse_silencedetect_output(stderr: &str) -> Vec<Silence> {
+    let value = |line: &str, label: &str| {
+        line.split_once(label)
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .and_then(|v| v.parse::<f64>().ok())
+            // silence at the very beginning is sometimes reported slightly below zero
+            .and_then(|secs| Duration::try_from_secs_f64(secs.max(0.0)).ok())
+    };
+
+    let mut silences = Vec::new();
+    let mut start = None;
+
+    for line in stderr.lines() {
+        if let Some(silence_start) = value(line, "silence_start:") {
+            start = Some(silence_start);
+        } else if let Some(end) = value(line, "silence_end:") {
+            if let Some(start) = start.take() {
+                silences.push(Silence { start, end });
+            }
+        }
+    }
+
+    silences
+}
+
+/// Parses how much audio ffmpeg processed from its final progress report:
+///
+/// ```text
+/// size=N/A time=00:59:58.42 bitrate=N/A speed= 812x
+/// ```
+fn parse_processed_duration(stderr: &str) -> Option<Duration> {
+    let (_, report) = stderr.rsplit_once("time=")?;
+    let time = report.split_whitespace().next()?;
+
+    parse_timestamp(time).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{FakeCommandRunner, ScriptedResponse};
+
+    /// Scripts the segmenting ffmpeg run, producing the given chunks and their segment list
+    fn segmented(dir: &Path, chunks: &[(&str, f64, f64, usize)]) -> ScriptedResponse {
+        let segment_list = chunks
+            .iter()
+            .map(|(name, start, end, _)| format!("{name},{start:.6},{end:.6}\n"))
+            .collect::<String>();
+
+        chunks.iter().fold(
+            ScriptedResponse::success().creates_file(dir.join(SEGMENT_LIST_FILE), segment_list),
+            |response, (name, _, _, bytes)| response.creates_file(dir.join(name), vec![0; *bytes]),
+        )
+    }
+
+    #[test]
+    fn conditions_and_segments_in_a_single_ffmpeg_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = FakeCommandRunner::new().respond(segmented(
+            dir.path(),
+            &[
+                ("in_000.mp3", 0.0, 900.0, 3),
+                ("in_001.mp3", 900.0, 1000.5, 1),
+            ],
+        ));
+        let ytdlp = YtDlp::fake(&runner);
+        let template = dir.path().join("in_%03d.mp3");
+
+        let chunks = ytdlp
+            .condition_and_segment(
+                "/tmp/in.mp3",
+                900,
+                &template,
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].has_arg_pair(
+            "-af",
+            "afftdn,loudnorm=I=-24:TP=-2:LRA=7,silenceremove=start_periods=1:start_threshold=-50dB:start_silence=0.1"
+        ));
+        assert!(calls[0].has_arg_pair("-f", "segment"));
+        assert!(calls[0].has_arg_pair("-segment_time", "900"));
+        assert!(calls[0].has_arg_pair("-segment_list_type", "csv"));
+        assert!(calls[0].has_arg_pair("-c:a", "libmp3lame"));
+        assert_eq!(calls[0].args.last().unwrap(), template.to_str().unwrap());
+
+        assert_eq!(
+            chunks,
+            vec![
+                AudioChunk {
+                    index: 0,
+                    path: dir.path().join("in_000.mp3"),
+                    start: Duration::ZERO,
+                    end: secs(900.0),
+                    bytes: 3,
+                },
+                AudioChunk {
+                    index: 1,
+                    path: dir.path().join("in_001.mp3"),
+                    start: secs(900.0),
+                    end: secs(1000.5),
+                    bytes: 1,
+                },
+            ]
+        );
+        // the segment list is only needed to build the manifest
+        assert!(!dir.path().join(SEGMENT_LIST_FILE).exists());
+    }
+
+    #[test]
+    fn round_trips_chunk_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("manifest.json");
+        let chunks = vec![AudioChunk {
+            index: 0,
+            path: dir.path().join("in_000.mp3"),
+            start: secs(12.25),
+            end: secs(900.5),
+            bytes: 1024,
+        }];
+
+        AudioChunk::write_manifest(&chunks, &manifest_path).unwrap();
+
+        let json = fs::read_to_string(&manifest_path).unwrap();
+        assert!(json.contains(r#""start": 12.25"#));
+        assert_eq!(AudioChunk::read_manifest(&manifest_path).unwrap(), chunks);
+    }
+
+    #[test]
+    fn parses_segment_lists() {
+        let segment_list = "a,b_000.mp3,0.000000,901.420000\n\"a,b_001.mp3\",901.420000,1795.5\n";
+
+        assert_eq!(
+            parse_segment_list(segment_list).unwrap(),
+            vec![
+                ("a,b_000.mp3", Duration::ZERO, secs(901.42)),
+                ("a,b_001.mp3", secs(901.42), secs(1795.5)),
+            ]
+        );
+        assert!(parse_segment_list("in_000.mp3,0.0").is_err());
+    }
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    fn silence(start: f64, end: f64) -> Silence {
+        Silence {
+            start: secs(start),
+            end: secs(end),
+        }
+    }
+
+    #[test]
+    fn parses_silencedetect_output() {
+        let stderr = "\
+[silencedetect @ 0x6000] silence_start: -0.0012
+[silencedetect @ 0x6000] silence_end: 1.5 | silence_duration: 1.5012
+size=N/A time=00:15:00.00 bitrate=N/A speed= 812x
+[silencedetect @ 0x6000] silence_start: 901.42
+[silencedetect @ 0x6000] silence_end: 903.1 | silence_duration: 1.68
+[silencedetect @ 0x6000] silence_start: 1790
+";
+
+        assert_eq!(
+            parse_silencedetect_output(stderr),
+            vec![silence(0.0, 1.5), silence(901.42, 903.1)]
+        );
+        assert_eq!(parse_processed_duration(stderr), Some(secs(900.0)));
+        assert_eq!(parse_processed_duration("size=N/A time=N/A"), None);
+    }
+
+    #[test]
+    fn cuts_at_the_nearest_pause_within_tolerance() {
+        let silences = [
+            silence(850.0, 851.0),
+            silence(880.0, 882.0),
+            silence(1795.0, 1796.0),
+        ];
+
+        let cuts = choose_cut_points(secs(2000.0), &silences, secs(900.0), secs(60.0));
+
+        // 881 is closer to 900 than 850.5, the second target (881 + 900) is closest to 1795.5
+        assert_eq!(cuts, vec![secs(881.0), secs(1795.5)]);
+    }
+
+    #[test]
+    fn cuts_inside_pauses_spanning_the_target() {
+        let cuts = choose_cut_points(
+            secs(1200.0),
+            &[silence(870.0, 1000.0)],
+            secs(900.0),
+            secs(60.0),
+        );
+
+        assert_eq!(cuts, vec![secs(900.0)]);
+    }
+
+    #[test]
+    fn falls_back_to_hard_cuts_without_pauses() {
+        let cuts = choose_cut_points(
+            secs(2000.0),
+            &[silence(500.0, 501.0)],
+            secs(900.0),
+            secs(60.0),
+        );
+
+        assert_eq!(cuts, vec![secs(900.0), secs(1800.0)]);
+    }
+
+    #[test]
+    fn short_audio_needs_no_cuts() {
+        assert!(choose_cut_points(secs(600.0), &[], secs(900.0), secs(60.0)).is_empty());
+    }
+
+    #[test]
+    fn splits_at_detected_silences() {
+        let dir = tempfile::tempdir().unwrap();
+        // trimming the leading silence shortened the audio to 1000s
+        let runner = FakeCommandRunner::new()
+            .respond(ScriptedResponse::success().stderr(
+                "[silencedetect @ 0x1] silence_start: 910\n[silencedetect @ 0x1] silence_end: 912 | silence_duration: 2\nsize=N/A time=00:16:40.00 bitrate=N/A speed= 900x\n",
+            ))
+            .respond(segmented(
+                dir.path(),
+                &[("in_000.mp3", 0.0, 911.0, 1), ("in_001.mp3", 911.0, 1000.0, 1)],
+            ));
+        let ytdlp = YtDlp::fake(&runner);
+
+        let chunks = ytdlp
+            .condition_and_segment_at_silences(
+                "/tmp/in.mp3",
+                900,
+                60,
+                dir.path().join("in_%03d.mp3"),
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].start, secs(911.0));
+
+        // the filtered duration comes from the detection pass rather than probing the input
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].has_arg_pair(
+            "-af",
+            "afftdn,loudnorm=I=-24:TP=-2:LRA=7,silenceremove=start_periods=1:start_threshold=-50dB:start_silence=0.1,silencedetect=noise=-35dB:d=0.5"
+        ));
+        assert!(calls[0].has_arg_pair("-f", "null"));
+        assert!(calls[1].has_arg_pair("-segment_times", "911.000"));
+        assert!(calls[1].has_arg_pair(
+            "-af",
+            "afftdn,loudnorm=I=-24:TP=-2:LRA=7,silenceremove=start_periods=1:start_threshold=-50dB:start_silence=0.1"
+        ));
+    }
+
+    #[test]
+    fn keeps_leading_silence_of_condensed_audio() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = FakeCommandRunner::new()
+            .respond(ScriptedResponse::success().stderr("size=N/A time=00:16:40.00 bitrate=N/A"))
+            .respond(segmented(dir.path(), &[("in_000.mp3", 0.0, 1000.0, 1)]));
+        let ytdlp = YtDlp::fake(&runner);
+
+        ytdlp
+            .condition_and_segment_at_silences(
+                "/tmp/in_condensed.flac",
+                900,
+                60,
+                dir.path().join("in_%03d.mp3"),
+                &AudioProcessingConfig {
+                    trim_leading_silence: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // the padding remove_long_silences leaves before the first words stays put
+        let calls = runner.calls();
+        assert!(calls[0].has_arg_pair(
+            "-af",
+            "afftdn,loudnorm=I=-24:TP=-2:LRA=7,silencedetect=noise=-35dB:d=0.5"
+        ));
+        assert!(calls[1].has_arg_pair("-af", "afftdn,loudnorm=I=-24:TP=-2:LRA=7"));
+    }
+
+    #[test]
+    fn estimates_segment_length_from_bitrate() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = FakeCommandRunner::new()
+            .respond(ScriptedResponse::success().stdout(
+                r#"{ "format": { "format_name": "mp3", "duration": "120.0", "bit_rate": "128000" }, "streams": [] }"#,
+            ))
+            .respond(segmented(
+                dir.path(),
+                &[("in_000.mp3", 0.0, 56.0, 10), ("in_001.mp3", 56.0, 112.0, 10), ("in_002.mp3", 112.0, 120.0, 2)],
+            ));
+        let ytdlp = YtDlp::fake(&runner);
+
+        // 1MB at 128kbps with 10% headroom is 56.25 seconds
+        let limits = ChunkLimits::max_bytes(1_000_000).with_max_duration(Duration::from_secs(900));
+        let chunks = ytdlp
+            .split_audio_to_limited_chunks(
+                "/tmp/in.mp3",
+                limits,
+                dir.path().join("in_%03d.mp3"),
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+
+        assert_eq!(chunks.len(), 3);
+        assert!(runner.calls()[1].has_arg_pair("-segment_time", "56"));
+    }
+
+    #[test]
+    fn duration_limit_applies_without_probing() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = FakeCommandRunner::new()
+            .respond(segmented(dir.path(), &[("in_000.mp3", 0.0, 600.0, 1)]));
+        let ytdlp = YtDlp::fake(&runner);
+
+        ytdlp
+            .split_audio_to_limited_chunks(
+                "/tmp/in.mp3",
+                ChunkLimits::max_duration(Duration::from_secs(900)),
+                dir.path().join("in_%03d.mp3"),
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].has_arg_pair("-segment_time", "900"));
+    }
+
+    #[test]
+    fn resplits_oversized_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("in_000.mp3"), [0; 10]).unwrap();
+        fs::write(dir.path().join("in_001.mp3"), [0; 30]).unwrap();
+        let chunk = |index: usize, start: f64, end: f64, bytes: u64| AudioChunk {
+            index,
+            path: dir.path().join(format!("in_{index:03}.mp3")),
+            start: secs(start),
+            end: secs(end),
+            bytes,
+        };
+
+        let runner = FakeCommandRunner::new()
+            .respond(ScriptedResponse::success().stderr(
+                "[silencedetect @ 0x1] silence_start: 52\n[silencedetect @ 0x1] silence_end: 53 | silence_duration: 1\n",
+            ))
+            .respond(segmented(
+                dir.path(),
+                &[
+                    ("in_001_000.mp3", 0.0, 52.5, 15),
+                    ("in_001_001.mp3", 52.5, 100.0, 15),
+                ],
+            ));
+        let ytdlp = YtDlp::fake(&runner);
+
+        let chunks = ytdlp
+            .fit_chunks_to_size(
+                vec![chunk(0, 0.0, 100.0, 10), chunk(1, 100.0, 200.0, 30)],
+                20,
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+
+        // 30 bytes into pieces of at most 18 needs two 50 second pieces, cut at the nearby pause
+        // without re-encoding
+        let calls = runner.calls();
+        assert!(calls[0].has_arg_pair("-i", dir.path().join("in_001.mp3").to_str().unwrap()));
+        assert!(calls[0].has_arg_pair("-f", "null"));
+        assert!(calls[1].has_arg_pair("-segment_times", "52.500"));
+        assert!(calls[1].has_arg_pair("-c:a", "copy"));
+        assert!(!calls[1].args.iter().any(|arg| arg == "-ar"));
+        assert!(!dir.path().join("in_001.mp3").exists());
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|c| (c.index, c.start, c.end, c.bytes))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, secs(0.0), secs(100.0), 10),
+                (1, secs(100.0), secs(152.5), 15),
+                (2, secs(152.5), secs(200.0), 15),
+            ]
+        );
+        assert_eq!(chunks[2].path, dir.path().join("in_001_001.mp3"));
+    }
+
+    #[test]
+    fn rejects_chunks_that_cannot_be_split_further() {
+        let dir = tempfile::tempdir().unwrap();
+        let chunk = AudioChunk {
+            index: 0,
+            path: dir.path().join("in_000.mp3"),
+            start: Duration::ZERO,
+            end: secs(1.5),
+            bytes: 100,
+        };
+        let runner = FakeCommandRunner::new();
+        let ytdlp = YtDlp::fake(&runner);
+
+        let result = ytdlp.fit_chunks_to_size(vec![chunk], 20, &AudioProcessingConfig::default());
+
+        assert!(matches!(result, Err(YtDlpError::ChunkTooLarge(_))));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[test]
+    fn picks_long_and_edge_silences_for_removal() {
+        let silences = [
+            silence(0.0, 4.0),
+            silence(100.0, 101.0),
+            silence(600.0, 1500.0),
+            silence(1990.0, 2000.0),
+        ];
+
+        let spans = removable_spans(secs(2000.0), &silences, secs(30.0));
+
+        assert_eq!(
+            spans,
+            vec![
+                (Duration::ZERO, secs(3.5)),
+                (secs(600.5), secs(1499.5)),
+                (secs(1990.5), secs(2000.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn builds_span_removal_filter() {
+        assert_eq!(remove_spans_filter(&[]), "anull");
+        assert_eq!(
+            remove_spans_filter(&[(Duration::ZERO, secs(4.0)), (secs(600.5), secs(1499.5))]),
+            "aselect='not(between(t,0.000,4.000)+between(t,600.500,1499.500))',asetpts=N/SR/TB"
+        );
+    }
+
+    #[test]
+    fn removes_long_silences_and_returns_time_map() {
+        let runner = FakeCommandRunner::new()
+            .respond(ScriptedResponse::success().stdout(
+                r#"{ "format": { "format_name": "mp3", "duration": "2000.0" }, "streams": [] }"#,
+            ))
+            .respond(ScriptedResponse::success().stderr(
+                "[silencedetect @ 0x1] silence_start: 600\n[silencedetect @ 0x1] silence_end: 1500 | silence_duration: 900\n",
+            ));
+        let ytdlp = YtDlp::fake(&runner);
+
+        let time_map = ytdlp
+            .remove_long_silences(
+                "/tmp/in.mp3",
+                secs(30.0),
+                "/tmp/out.flac",
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+
+        assert_eq!(time_map.removed_duration(), secs(899.0));
+        assert_eq!(time_map.to_original(secs(700.0)), secs(1599.0));
+
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 3);
+        assert!(calls[2].has_arg_pair(
+            "-af",
+            "aselect='not(between(t,600.500,1499.500))',asetpts=N/SR/TB"
+        ));
+        assert!(calls[2].has_arg_pair("-c:a", "flac"));
+    }
+
+    #[test]
+    fn measures_loudness_first_for_two_pass_normalization() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = FakeCommandRunner::new()
+            .respond(ScriptedResponse::success().stderr(
+                r#"{ "input_i": "-27.61", "input_tp": "-4.47", "input_lra": "18.06", "input_thresh": "-39.20", "target_offset": "0.58" }"#,
+            ))
+            .respond(segmented(dir.path(), &[("in_000.opus", 0.0, 600.0, 1)]));
+        let ytdlp = YtDlp::fake(&runner);
+        let config = AudioProcessingConfig {
+            two_pass_loudnorm: true,
+            bit_rate: Some(24_000),
+            ..Default::default()
+        };
+
+        ytdlp
+            .condition_and_segment("/tmp/in.mp3", 900, dir.path().join("in_%03d.opus"), &config)
+            .unwrap();
+
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].has_arg_pair("-af", "afftdn,loudnorm=I=-24:TP=-2:LRA=7:print_format=json"));
+        assert!(calls[1].has_arg_pair(
+            "-af",
+            "afftdn,loudnorm=I=-24:TP=-2:LRA=7:measured_I=-27.61:measured_TP=-4.47:measured_LRA=18.06:measured_thresh=-39.20:offset=0.58:linear=true,silenceremove=start_periods=1:start_threshold=-50dB:start_silence=0.1"
+        ));
+        assert!(calls[1].has_arg_pair("-c:a", "libopus"));
+        assert!(calls[1].has_arg_pair("-b:a", "24000"));
+    }
+
+    const PROBED_MP3: &str = r#"{
+        "format": { "format_name": "mp3", "duration": "600.0" },
+        "streams": [{ "index": 0, "codec_type": "audio", "codec_name": "mp3" }]
+    }"#;
+
+    fn range(start: f64, end: f64) -> TimeRange {
+        TimeRange::new(secs(start), secs(end)).unwrap()
+    }
+
+    #[test]
+    fn copies_clips_without_fades() {
+        let runner =
+            FakeCommandRunner::new().respond(ScriptedResponse::success().stdout(PROBED_MP3));
+        let ytdlp = YtDlp::fake(&runner);
+
+        ytdlp
+            .extract_clip(
+                "/tmp/in.mp3",
+                range(120.5, 150.0),
+                Duration::ZERO,
+                "/tmp/clip.mp3",
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+
+        let calls = runner.calls();
+        assert!(calls[1].has_arg_pair("-ss", "120.500"));
+        assert!(calls[1].has_arg_pair("-t", "29.500"));
+        assert!(calls[1].has_arg_pair("-c:a", "copy"));
+        assert!(!calls[1].args.contains(&"-af".to_string()));
+    }
+
+    #[test]
+    fn re_encodes_clips_with_fades() {
+        let runner =
+            FakeCommandRunner::new().respond(ScriptedResponse::success().stdout(PROBED_MP3));
+        let ytdlp = YtDlp::fake(&runner);
+
+        // runs past the end, so it's cut to 10s
+        ytdlp
+            .extract_clip(
+                "/tmp/in.mp3",
+                range(590.0, 620.0),
+                secs(1.0),
+                "/tmp/clip.opus",
+                &AudioProcessingConfig::default(),
+            )
+            .unwrap();
+
+        let calls = runner.calls();
+        assert!(calls[1].has_arg_pair("-t", "10.000"));
+        assert!(calls[1].has_arg_pair("-af", "afade=t=in:d=1.000,afade=t=out:st=9.000:d=1.000"));
+        assert!(calls[1].has_arg_pair("-c:a", "libopus"));
+    }
+
+    #[test]
+    fn rejects_clips_past_the_end() {
+        let runner =
+            FakeCommandRunner::new().respond(ScriptedResponse::success().stdout(PROBED_MP3));
+        let ytdlp = YtDlp::fake(&runner);
+
+        let result = ytdlp.extract_clip(
+            "/tmp/in.mp3",
+            range(600.0, 620.0),
+            secs(1.0),
+            "/tmp/clip.mp3",
+            &AudioProcessingConfig::default(),
+        );
+
+        assert!(matches!(result, Err(YtDlpError::InvalidTimeRange(_))));
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    #[test]
+    fn rejects_unsupported_chunk_formats() {
+        let runner = FakeCommandRunner::new();
+        let ytdlp = YtDlp::fake(&runner);
+
+        let result = ytdlp.condition_and_segment(
+            "/tmp/in.mp3",
+            900,
+            "/tmp/chunks/in_%03d.mkv",
+            &AudioProcessingConfig::default(),
+        );
+
+        assert!(matches!(result, Err(YtDlpError::UnsupportedFormat(ext)) if ext == "mkv"));
+
+        // a chunk without an extension can't be re-split into pieces of the same format
+        let result = ytdlp.fit_chunks_to_size(
+            vec![AudioChunk {
+                index: 0,
+                path: PathBuf::from("/tmp/chunks/in_000"),
+                start: Duration::ZERO,
+                end: secs(100.0),
+                bytes: 100,
+            }],
+            20,
+            &AudioProcessingConfig::default(),
+        );
+        assert!(matches!(result, Err(YtDlpError::UnsupportedFormat(_))));
+        assert!(runner.calls().is_empty());
     }
 }
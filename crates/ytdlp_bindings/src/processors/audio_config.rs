@@ -0,0 +1,415 @@
+//! # audio_config
+//!
+//! Tunable ffmpeg parameters shared by the [`AudioProcessor`](crate::AudioProcessor) methods.
+
+use std::{path::Path, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{YtDlp, YtDlpError};
+
+/// Audio encoders supported by [`AudioProcessor`](crate::AudioProcessor).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioCodec {
+    /// 16-bit PCM, for `.wav`
+    Pcm16,
+    /// For `.mp3`
+    Mp3,
+    /// For `.flac`
+    Flac,
+    /// For `.aac` and `.m4a`
+    Aac,
+    /// For `.opus`, also valid in `.ogg` and `.webm`
+    Opus,
+    /// For `.ogg`
+    Vorbis,
+}
+
+impl AudioCodec {
+    /// Picks the usual codec for a file extension.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "wav" => Some(AudioCodec::Pcm16),
+            "mp3" => Some(AudioCodec::Mp3),
+            "flac" => Some(AudioCodec::Flac),
+            "aac" | "m4a" => Some(AudioCodec::Aac),
+            "opus" => Some(AudioCodec::Opus),
+            "ogg" => Some(AudioCodec::Vorbis),
+            _ => None,
+        }
+    }
+
+    /// Name of the matching ffmpeg encoder
+    pub fn encoder(&self) -> &'static str {
+        match self {
+            AudioCodec::Pcm16 => "pcm_s16le",
+            AudioCodec::Mp3 => "libmp3lame",
+            AudioCodec::Flac => "flac",
+            AudioCodec::Aac => "aac",
+            AudioCodec::Opus => "libopus",
+            AudioCodec::Vorbis => "libvorbis",
+        }
+    }
+
+    /// Codec name as reported by ffprobe
+    pub(crate) fn probe_name(&self) -> &'static str {
+        match self {
+            AudioCodec::Pcm16 => "pcm_s16le",
+            AudioCodec::Mp3 => "mp3",
+            AudioCodec::Flac => "flac",
+            AudioCodec::Aac => "aac",
+            AudioCodec::Opus => "opus",
+            AudioCodec::Vorbis => "vorbis",
+        }
+    }
+
+    /// Lossless codecs ignore [`AudioProcessingConfig::bit_rate`]
+    pub fn is_lossless(&self) -> bool {
+        matches!(self, AudioCodec::Pcm16 | AudioCodec::Flac)
+    }
+}
+
+/// EBU R128 targets for loudness normalization, ffmpeg's `loudnorm` defaults by default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessTarget {
+    /// Integrated loudness in LUFS
+    pub integrated_lufs: f64,
+    /// Maximum true peak in dBTP
+    pub true_peak_db: f64,
+    /// Loudness range in LU
+    pub loudness_range_lu: f64,
+}
+
+impl Default for LoudnessTarget {
+    fn default() -> Self {
+        LoudnessTarget {
+            integrated_lufs: -24.0,
+            true_peak_db: -2.0,
+            loudness_range_lu: 7.0,
+        }
+    }
+}
+
+/// Parameters for [`AudioProcessor`](crate::AudioProcessor) methods.
+///
+/// The defaults produce 16kHz mono audio, which is what speech-to-text models expect.
+///
+/// ```rust
+/// use ytdlp_bindings::{AudioCodec, AudioProcessingConfig};
+///
+/// // smaller uploads at the cost of some quality
+/// let config = AudioProcessingConfig {
+///     codec: Some(AudioCodec::Opus),
+///     bit_rate: Some(24_000),
+///     two_pass_loudnorm: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioProcessingConfig {
+    /// Output sample rate in Hz
+    pub sample_rate: u32,
+    /// Output channel count
+    pub channels: u32,
+    /// Output bitrate in bits per second for lossy codecs, the encoder's default if unset
+    pub bit_rate: Option<u32>,
+    /// Output codec, inferred from the output file extension if unset.
+    ///
+    /// Applies to every output written with the config, so intermediates of another format,
+    /// e.g. a lossless `.flac`, need a config of their own leaving this unset.
+    pub codec: Option<AudioCodec>,
+    /// Noise floor in dB for `afftdn` denoising (-80 to -20), ffmpeg's default if unset
+    pub denoise_noise_floor_db: Option<f64>,
+    /// Targets for `loudnorm`
+    pub loudness: LoudnessTarget,
+    /// Measure loudness in a first pass and normalize linearly in a second one.
+    ///
+    /// More accurate than single-pass (dynamic) normalization, at the cost of decoding
+    /// the input once more.
+    pub two_pass_loudnorm: bool,
+    /// Trim leading silence while conditioning.
+    ///
+    /// Turn off for audio whose silences were already cut, e.g. by
+    /// [`AudioProcessor::remove_long_silences`](crate::AudioProcessor::remove_long_silences),
+    /// so that chunk offsets stay on the timeline its [`TimeMap`](crate::TimeMap) maps back.
+    pub trim_leading_silence: bool,
+    /// Anything quieter than this (dB) at the very start is trimmed
+    pub leading_silence_threshold_db: f64,
+    /// Minimum length of the leading silence to trim
+    pub leading_silence_duration: Duration,
+    /// Anything quieter than this (dB) counts as a pause when detecting silences
+    pub pause_threshold_db: f64,
+    /// Minimum length of a detected pause
+    pub min_pause: Duration,
+}
+
+impl Default for AudioProcessingConfig {
+    fn default() -> Self {
+        AudioProcessingConfig {
+            sample_rate: 16_000,
+            channels: 1,
+            bit_rate: None,
+            codec: None,
+            denoise_noise_floor_db: None,
+            loudness: LoudnessTarget::default(),
+            two_pass_loudnorm: false,
+            trim_leading_silence: true,
+            leading_silence_threshold_db: -50.0,
+            leading_silence_duration: Duration::from_millis(100),
+            pause_threshold_db: -35.0,
+            min_pause: Duration::from_millis(500),
+        }
+    }
+}
+
+impl AudioProcessingConfig {
+    /// The configured codec, or the one matching `output_path`'s extension.
+    pub(crate) fn codec_for(&self, output_path: &Path) -> Result<AudioCodec, YtDlpError> {
+        if let Some(codec) = self.codec {
+            return Ok(codec);
+        }
+
+        let ext = output_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        AudioCodec::from_extension(&ext).ok_or(YtDlpError::UnsupportedFormat(ext))
+    }
+
+    /// Output options for encoding with `codec`, e.g. `-ar 16000 -ac 1 -c:a libmp3lame`.
+    pub(crate) fn encoding_args(&self, codec: AudioCodec) -> Vec<String> {
+        let mut args = vec![
+            "-ar".to_string(),
+            self.sample_rate.to_string(),
+            "-ac".to_string(),
+            self.channels.to_string(),
+            "-c:a".to_string(),
+            codec.encoder().to_string(),
+        ];
+
+        if let (Some(bit_rate), false) = (self.bit_rate, codec.is_lossless()) {
+            args.extend(["-b:a".to_string(), bit_rate.to_string()]);
+        }
+
+        args
+    }
+
+    /// Bitrate (bits per second) of the output when encoding with `codec`, if known upfront.
+    pub(crate) fn output_bit_rate(&self, codec: AudioCodec) -> Option<u64> {
+        match codec {
+            AudioCodec::Pcm16 => Some(16 * u64::from(self.sample_rate) * u64::from(self.channels)),
+            AudioCodec::Flac => None,
+            _ => self.bit_rate.map(u64::from),
+        }
+    }
+
+    pub(crate) fn denoise_filter(&self) -> String {
+        match self.denoise_noise_floor_db {
+            Some(noise_floor) => format!("afftdn=nf={noise_floor}"),
+            None => "afftdn".to_string(),
+        }
+    }
+
+    /// The `loudnorm` filter, normalizing linearly when given a first pass' measurement.
+    pub(crate) fn normalize_filter(&self, measured: Option<&LoudnormMeasurement>) -> String {
+        let LoudnessTarget {
+            integrated_lufs,
+            true_peak_db,
+            loudness_range_lu,
+        } = self.loudness;
+        let target =
+            format!("loudnorm=I={integrated_lufs}:TP={true_peak_db}:LRA={loudness_range_lu}");
+
+        match measured {
+            Some(m) => format!(
+                "{target}:measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true",
+                m.input_i, m.input_tp, m.input_lra, m.input_thresh, m.target_offset
+            ),
+            None => target,
+        }
+    }
+
+    pub(crate) fn trim_silence_filter(&self) -> String {
+        format!(
+            "silenceremove=start_periods=1:start_threshold={}dB:start_silence={}",
+            self.leading_silence_threshold_db,
+            self.leading_silence_duration.as_secs_f64()
+        )
+    }
+
+    pub(crate) fn detect_silence_filter(&self) -> String {
+        format!(
+            "silencedetect=noise={}dB:d={}",
+            self.pause_threshold_db,
+            self.min_pause.as_secs_f64()
+        )
+    }
+
+    /// Builds the normalization filter, running the measurement pass (through `pre_filters`)
+    /// first when two-pass normalization is enabled.
+    pub(crate) fn resolve_normalize_filter(
+        &self,
+        ytdlp: &YtDlp,
+        input_path: &Path,
+        pre_filters: Option<&str>,
+    ) -> Result<String, YtDlpError> {
+        if !self.two_pass_loudnorm {
+            return Ok(self.normalize_filter(None));
+        }
+
+        let input_str = input_path
+            .to_str()
+            .ok_or_else(|| YtDlpError::InvalidPath(input_path.display().to_string()))?;
+        let measure_filter = format!("{}:print_format=json", self.normalize_filter(None));
+        let filter_graph = match pre_filters {
+            Some(pre_filters) => format!("{pre_filters},{measure_filter}"),
+            None => measure_filter,
+        };
+
+        let stderr = ytdlp.run_ffmpeg_with_output(&[
+            "-nostats",
+            "-i",
+            input_str,
+            "-af",
+            &filter_graph,
+            "-f",
+            "null",
+            "-",
+        ])?;
+
+        Ok(self.normalize_filter(Some(&parse_loudnorm_measurement(&stderr)?)))
+    }
+}
+
+/// First pass `loudnorm` measurements, which ffmpeg reports as strings.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct LoudnormMeasurement {
+    input_i: String,
+    input_tp: String,
+    input_lra: String,
+    input_thresh: String,
+    target_offset: String,
+}
+
+/// Parses the JSON block `loudnorm=print_format=json` prints to stderr once the input is exhausted.
+fn parse_loudnorm_measurement(stderr: &str) -> Result<LoudnormMeasurement, YtDlpError> {
+    let json = stderr
+        .rfind('{')
+        .zip(stderr.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| &stderr[start..=end])
+        .ok_or_else(|| {
+            YtDlpError::ProbeError("loudnorm measurement not found in ffmpeg output".to_string())
+        })?;
+
+    Ok(serde_json::from_str(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_speech_to_text_settings() {
+        let config = AudioProcessingConfig::default();
+
+        assert_eq!(
+            config.encoding_args(AudioCodec::Mp3),
+            ["-ar", "16000", "-ac", "1", "-c:a", "libmp3lame"]
+        );
+        assert_eq!(config.denoise_filter(), "afftdn");
+        assert_eq!(config.normalize_filter(None), "loudnorm=I=-24:TP=-2:LRA=7");
+        assert_eq!(
+            config.trim_silence_filter(),
+            "silenceremove=start_periods=1:start_threshold=-50dB:start_silence=0.1"
+        );
+        assert_eq!(
+            config.detect_silence_filter(),
+            "silencedetect=noise=-35dB:d=0.5"
+        );
+    }
+
+    #[test]
+    fn applies_custom_settings() {
+        let config = AudioProcessingConfig {
+            sample_rate: 24_000,
+            bit_rate: Some(24_000),
+            codec: Some(AudioCodec::Opus),
+            denoise_noise_floor_db: Some(-30.0),
+            ..Default::default()
+        };
+
+        let codec = config.codec_for(Path::new("/tmp/out.ogg")).unwrap();
+        assert_eq!(codec, AudioCodec::Opus);
+        assert_eq!(
+            config.encoding_args(codec),
+            ["-ar", "24000", "-ac", "1", "-c:a", "libopus", "-b:a", "24000"]
+        );
+        assert_eq!(config.denoise_filter(), "afftdn=nf=-30");
+        // lossless codecs have no bitrate to set
+        assert!(!config
+            .encoding_args(AudioCodec::Flac)
+            .contains(&"-b:a".to_string()));
+    }
+
+    #[test]
+    fn infers_codecs_from_extensions() {
+        let config = AudioProcessingConfig::default();
+
+        assert_eq!(
+            config.codec_for(Path::new("a.opus")).unwrap(),
+            AudioCodec::Opus
+        );
+        assert_eq!(
+            config.codec_for(Path::new("a.OGG")).unwrap(),
+            AudioCodec::Vorbis
+        );
+        assert_eq!(
+            config.codec_for(Path::new("a.m4a")).unwrap(),
+            AudioCodec::Aac
+        );
+        assert!(matches!(
+            config.codec_for(Path::new("a.txt")),
+            Err(YtDlpError::UnsupportedFormat(ext)) if ext == "txt"
+        ));
+    }
+
+    #[test]
+    fn builds_second_pass_loudnorm_filter() {
+        let stderr = r#"
+[Parsed_loudnorm_1 @ 0x600001a5c000]
+{
+	"input_i" : "-27.61",
+	"input_tp" : "-4.47",
+	"input_lra" : "18.06",
+	"input_thresh" : "-39.20",
+	"output_i" : "-16.58",
+	"output_tp" : "-1.50",
+	"output_lra" : "14.78",
+	"output_thresh" : "-27.71",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.58"
+}
+"#;
+        let measured = parse_loudnorm_measurement(stderr).unwrap();
+        let config = AudioProcessingConfig {
+            loudness: LoudnessTarget {
+                integrated_lufs: -16.0,
+                true_peak_db: -1.5,
+                loudness_range_lu: 11.0,
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.normalize_filter(Some(&measured)),
+            "loudnorm=I=-16:TP=-1.5:LRA=11:measured_I=-27.61:measured_TP=-4.47:measured_LRA=18.06:measured_thresh=-39.20:offset=0.58:linear=true"
+        );
+    }
+
+    #[test]
+    fn rejects_missing_loudnorm_measurement() {
+        assert!(parse_loudnorm_measurement("Output #0, null, to 'pipe:':").is_err());
+    }
+}
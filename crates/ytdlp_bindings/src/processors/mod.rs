@@ -1,3 +1,5 @@
 pub mod audio;
+pub mod audio_config;
+pub mod probe;
 pub mod video;
 pub mod vtt;
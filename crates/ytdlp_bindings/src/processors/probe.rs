@@ -0,0 +1,348 @@
+//! # probe
+//!
+//! Enrich `YtDlp` by adding media inspection capabilities via `ffprobe`,
+//! as well as loudness measurements via ffmpeg's `ebur128` filter.
+
+use std::{path::Path, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{YtDlp, YtDlpError};
+
+/// Summary of a media file as reported by `ffprobe`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaInfo {
+    /// Container duration
+    pub duration: Duration,
+    /// Container format(s), e.g. `"mp3"` or `"mov,mp4,m4a,3gp,3g2,mj2"`
+    pub format_name: String,
+    /// Overall bitrate in bits per second
+    pub bit_rate: Option<u64>,
+    /// File size in bytes
+    pub size: Option<u64>,
+    pub streams: Vec<MediaStream>,
+}
+
+/// A single stream within a media file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaStream {
+    pub index: u32,
+    /// `"audio"`, `"video"`, `"subtitle"`...
+    pub codec_type: String,
+    pub codec_name: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    /// Stream bitrate in bits per second
+    pub bit_rate: Option<u64>,
+    pub duration: Option<Duration>,
+}
+
+/// EBU R128 loudness measurements of a media file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessStats {
+    /// Integrated loudness in LUFS
+    pub integrated_lufs: f64,
+    /// Loudness range in LU
+    pub loudness_range_lu: f64,
+    /// True peak in dBFS
+    pub true_peak_dbfs: Option<f64>,
+}
+
+impl MediaInfo {
+    /// Number of streams in the file
+    pub fn stream_count(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// The first audio stream, if any
+    pub fn audio_stream(&self) -> Option<&MediaStream> {
+        self.streams.iter().find(|s| s.codec_type == "audio")
+    }
+
+    /// Number of chunks produced when splitting this file into segments of `segment_time`
+    pub fn chunk_count(&self, segment_time: Duration) -> usize {
+        if segment_time.is_zero() {
+            return 0;
+        }
+        (self.duration.as_secs_f64() / segment_time.as_secs_f64()).ceil() as usize
+    }
+}
+
+/// A trait for inspecting media files using `ffprobe`.
+/// Requires `ffprobe` and `ffmpeg` v7+ available in the environment.
+pub trait MediaProber {
+    /// Inspect a media file's container and streams.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if `ffprobe` fails or its output cannot be parsed.
+    fn probe(&self, input_path: impl AsRef<Path>) -> Result<MediaInfo, YtDlpError>;
+
+    /// Measure loudness using the EBU R128 (`ebur128`) filter.
+    ///
+    /// This decodes the entire file, so expect it to take a while on long recordings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if `ffmpeg` fails or the loudness summary cannot be parsed.
+    fn loudness_stats(&self, input_path: impl AsRef<Path>) -> Result<LoudnessStats, YtDlpError>;
+}
+
+impl MediaProber for YtDlp {
+    fn probe(&self, input_path: impl AsRef<Path>) -> Result<MediaInfo, YtDlpError> {
+        let input_str = input_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| YtDlpError::InvalidPath(input_path.as_ref().display().to_string()))?;
+
+        let output = self.run_ffprobe(&[
+            "-v",
+            "error",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+            input_str,
+        ])?;
+
+        parse_ffprobe_output(&output)
+    }
+
+    fn loudness_stats(&self, input_path: impl AsRef<Path>) -> Result<LoudnessStats, YtDlpError> {
+        let input_str = input_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| YtDlpError::InvalidPath(input_path.as_ref().display().to_string()))?;
+
+        let stderr = self.run_ffmpeg_with_output(&[
+            "-nostats",
+            "-i",
+            input_str,
+            "-filter_complex",
+            // per-frame measurements are logged at verbose level, leaving just the summary
+            "ebur128=peak=true:framelog=verbose",
+            "-f",
+            "null",
+            "-",
+        ])?;
+
+        parse_ebur128_summary(&stderr)
+    }
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    format: FfprobeFormat,
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+}
+
+// ffprobe reports most numeric values as strings
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    format_name: String,
+    duration: Option<String>,
+    bit_rate: Option<String>,
+    size: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    index: u32,
+    codec_type: String,
+    codec_name: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<u32>,
+    bit_rate: Option<String>,
+    duration: Option<String>,
+}
+
+fn parse_ffprobe_output(output: &str) -> Result<MediaInfo, YtDlpError> {
+    let FfprobeOutput { format, streams } = serde_json::from_str(output)?;
+
+    let streams = streams
+        .into_iter()
+        .map(|s| MediaStream {
+            index: s.index,
+            codec_type: s.codec_type,
+            codec_name: s.codec_name,
+            sample_rate: s.sample_rate.and_then(|v| v.parse().ok()),
+            channels: s.channels,
+            bit_rate: s.bit_rate.and_then(|v| v.parse().ok()),
+            duration: s.duration.as_deref().and_then(parse_seconds),
+        })
+        .collect::<Vec<_>>();
+
+    // some containers only report durations on their streams
+    let duration = format
+        .duration
+        .as_deref()
+        .and_then(parse_seconds)
+        .or_else(|| streams.iter().filter_map(|s| s.duration).max())
+        .ok_or_else(|| {
+            YtDlpError::ProbeError(format!(
+                "ffprobe reported no duration for '{}'",
+                format.format_name
+            ))
+        })?;
+
+    Ok(MediaInfo {
+        duration,
+        format_name: format.format_name,
+        bit_rate: format.bit_rate.and_then(|v| v.parse().ok()),
+        size: format.size.and_then(|v| v.parse().ok()),
+        streams,
+    })
+}
+
+fn parse_seconds(value: &str) -> Option<Duration> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+}
+
+/// Parses the summary the `ebur128` filter prints to stderr once the input is exhausted:
+///
+/// ```text
+/// [Parsed_ebur128_0 @ 0x...] Summary:
+///
+///   Integrated loudness:
+///     I:         -19.5 LUFS
+///     Threshold: -29.7 LUFS
+///
+///   Loudness range:
+///     LRA:         5.5 LU
+///     ...
+///
+///   True peak:
+///     Peak:       -0.4 dBFS
+/// ```
+fn parse_ebur128_summary(stderr: &str) -> Result<LoudnessStats, YtDlpError> {
+    let summary = stderr
+        .rfind("Summary:")
+        .map(|idx| &stderr[idx..])
+        .ok_or_else(|| {
+            YtDlpError::ProbeError("ebur128 summary not found in ffmpeg output".to_string())
+        })?;
+
+    let value = |label: &str| {
+        summary
+            .lines()
+            .map(str::trim)
+            .find_map(|line| line.strip_prefix(label))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|v| v.parse::<f64>().ok())
+    };
+
+    match (value("I:"), value("LRA:")) {
+        (Some(integrated_lufs), Some(loudness_range_lu)) => Ok(LoudnessStats {
+            integrated_lufs,
+            loudness_range_lu,
+            true_peak_dbfs: value("Peak:"),
+        }),
+        _ => Err(YtDlpError::ProbeError(format!(
+            "Malformed ebur128 summary: {summary}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FFPROBE_MP3: &str = r#"{
+        "streams": [
+            {
+                "index": 0,
+                "codec_name": "mp3",
+                "codec_type": "audio",
+                "sample_rate": "48000",
+                "channels": 2,
+                "bit_rate": "128000",
+                "duration": "10812.048000"
+            }
+        ],
+        "format": {
+            "filename": "p40gmygQL2c.mp3",
+            "nb_streams": 1,
+            "format_name": "mp3",
+            "duration": "10812.048000",
+            "size": "173000000",
+            "bit_rate": "128004"
+        }
+    }"#;
+
+    #[test]
+    fn parses_ffprobe_json() {
+        let info = parse_ffprobe_output(FFPROBE_MP3).unwrap();
+
+        assert_eq!(info.duration, Duration::from_secs_f64(10812.048));
+        assert_eq!(info.format_name, "mp3");
+        assert_eq!(info.bit_rate, Some(128004));
+        assert_eq!(info.size, Some(173_000_000));
+        assert_eq!(info.stream_count(), 1);
+
+        let audio = info.audio_stream().unwrap();
+        assert_eq!(audio.codec_name.as_deref(), Some("mp3"));
+        assert_eq!(audio.sample_rate, Some(48000));
+        assert_eq!(audio.channels, Some(2));
+        assert_eq!(audio.bit_rate, Some(128000));
+    }
+
+    #[test]
+    fn falls_back_to_stream_duration() {
+        let output = r#"{
+            "streams": [{ "index": 0, "codec_type": "audio", "duration": "12.5" }],
+            "format": { "format_name": "ogg" }
+        }"#;
+
+        let info = parse_ffprobe_output(output).unwrap();
+        assert_eq!(info.duration, Duration::from_millis(12_500));
+    }
+
+    #[test]
+    fn computes_chunk_count() {
+        let info = parse_ffprobe_output(FFPROBE_MP3).unwrap();
+
+        assert_eq!(info.chunk_count(Duration::from_secs(900)), 13);
+        assert_eq!(info.chunk_count(Duration::from_secs(10812)), 2);
+        assert_eq!(info.chunk_count(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn parses_ebur128_summary() {
+        let stderr = r#"
+[Parsed_ebur128_0 @ 0x600002f1c000] t: 10811.9     TARGET:-23 LUFS    M: -21.3 S: -20.9     I: -19.6 LUFS       LRA:   5.4 LU  FTPK: -3.1 dBFS  TPK: -0.4 dBFS
+[Parsed_ebur128_0 @ 0x600002f1c000] Summary:
+
+  Integrated loudness:
+    I:         -19.5 LUFS
+    Threshold: -29.7 LUFS
+
+  Loudness range:
+    LRA:         5.5 LU
+    Threshold: -39.6 LUFS
+    LRA low:   -23.6 LUFS
+    LRA high:  -18.1 LUFS
+
+  True peak:
+    Peak:       -0.4 dBFS
+"#;
+
+        let stats = parse_ebur128_summary(stderr).unwrap();
+        assert_eq!(
+            stats,
+            LoudnessStats {
+                integrated_lufs: -19.5,
+                loudness_range_lu: 5.5,
+                true_peak_dbfs: Some(-0.4),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_missing_ebur128_summary() {
+        assert!(parse_ebur128_summary("Output #0, null, to 'pipe:':").is_err());
+    }
+}
@@ -3,7 +3,119 @@
 //! Enrich `YtDlp` by adding video processing capabilities
 
 use crate::{error::YtDlpError, YtDlp};
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Image formats for extracted frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Jpeg,
+    WebP,
+    Png,
+}
+
+impl ImageFormat {
+    /// Picks the format matching a file extension.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "webp" => Some(ImageFormat::WebP),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+
+    /// The usual file extension, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Png => "png",
+        }
+    }
+
+    /// Name of the matching ffmpeg encoder
+    pub fn encoder(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "mjpeg",
+            ImageFormat::WebP => "libwebp",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// Options for [`VideoProcessor`] frame extraction.
+///
+/// ```rust
+/// use ytdlp_bindings::{FrameOptions, ImageFormat};
+///
+/// // 640px wide WebP thumbnails
+/// let options = FrameOptions {
+///     width: Some(640),
+///     format: Some(ImageFormat::WebP),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameOptions {
+    /// Width to scale frames to, keeping the aspect ratio. Frames keep the video's size if unset
+    pub width: Option<u32>,
+    /// Image format, inferred from the output extension if unset. [`VideoProcessor::frames_at`]
+    /// writes JPEGs if unset
+    pub format: Option<ImageFormat>,
+    /// Quality from 1 (smallest) to 100 (best), ignored for PNGs
+    pub quality: u8,
+}
+
+impl Default for FrameOptions {
+    fn default() -> Self {
+        FrameOptions {
+            width: None,
+            format: None,
+            quality: 80,
+        }
+    }
+}
+
+impl FrameOptions {
+    fn format_for(&self, output_path: &Path) -> Result<ImageFormat, YtDlpError> {
+        if let Some(format) = self.format {
+            return Ok(format);
+        }
+
+        let ext = output_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        ImageFormat::from_extension(&ext).ok_or(YtDlpError::UnsupportedFormat(ext))
+    }
+
+    fn scale_filter(&self) -> Option<String> {
+        // -2 keeps the height even, which some encoders require
+        self.width.map(|width| format!("scale={width}:-2"))
+    }
+
+    /// Output options for encoding frames as `format`, e.g. `-c:v mjpeg -q:v 7`.
+    fn encoding_args(&self, format: ImageFormat) -> Vec<String> {
+        let quality = u32::from(self.quality.clamp(1, 100));
+        let mut args = vec!["-c:v".to_string(), format.encoder().to_string()];
+
+        match format {
+            // mjpeg's scale runs from 2 (best) to 31 (worst)
+            ImageFormat::Jpeg => args.extend([
+                "-q:v".to_string(),
+                (2 + (100 - quality) * 29 / 100).to_string(),
+            ]),
+            ImageFormat::WebP => args.extend(["-quality".to_string(), quality.to_string()]),
+            ImageFormat::Png => {}
+        }
+
+        args
+    }
+}
 
 /// A trait for processing video files.
 /// Requires `ffmpeg` v7* available in the environment
@@ -23,24 +135,51 @@ pub trait VideoProcessor {
         output_path: P,
     ) -> Result<(), YtDlpError>;
 
-    /// Extracts frames from a video
+    /// Extracts frames from a video at a fixed rate
     ///
     /// # Arguments
     /// * `video_input_path` - Path to video
-    /// * `fps`: Set frames per second options e.g "1", "1/10" or "fps=1,scale=320:24"
-    /// * `out_template` - Path/ template string of the frame PNG image files
-    /// * `extra_args` - Optional extra args to pass to the underlying ffmpeg command
+    /// * `fps`: Frames per second e.g "1" or "1/10"
+    /// * `output_template` - Path/ template string of the frame image files, e.g. `frame_%04d.jpg`
+    /// * `options` - Size, format and quality of the frames
     ///
     /// # Errors
     ///
-    /// Returns `YtDlpError` if the file cannot be read.
+    /// Returns `YtDlpError` if the file cannot be read or the image format is not supported.
     fn extract_frames<P: AsRef<Path>>(
         &self,
         input_path: P,
         fps: &str,
         output_template: P,
-        extra_args: Option<&[&str]>,
+        options: &FrameOptions,
     ) -> Result<(), YtDlpError>;
+
+    /// Extracts the frames shown at exact timestamps, e.g. as chapter thumbnails.
+    ///
+    /// Frames are written to `output_dir` as `frame_000.jpg`, `frame_001.jpg`... in the order of
+    /// `timestamps`, replacing existing files.
+    ///
+    /// # Arguments
+    /// * `input_path` - Path to video
+    /// * `timestamps` - Positions in the video to grab frames at
+    /// * `output_dir` - Directory to write the frames to, created if missing
+    /// * `options` - Size, format and quality of the frames
+    ///
+    /// # Returns
+    ///
+    /// The paths of the frames, in the order of `timestamps`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the file cannot be read, or [`YtDlpError::InvalidTimeRange`] if
+    /// a timestamp is past the end of the video.
+    fn frames_at(
+        &self,
+        input_path: impl AsRef<Path>,
+        timestamps: &[Duration],
+        output_dir: impl AsRef<Path>,
+        options: &FrameOptions,
+    ) -> Result<Vec<PathBuf>, YtDlpError>;
 }
 
 impl VideoProcessor for YtDlp {
@@ -66,7 +205,7 @@ impl VideoProcessor for YtDlp {
         input_path: P,
         fps: &str,
         output_template: P,
-        extra_args: Option<&[&str]>,
+        options: &FrameOptions,
     ) -> Result<(), YtDlpError> {
         let input_str = input_path
             .as_ref()
@@ -75,10 +214,175 @@ impl VideoProcessor for YtDlp {
         let output_str = output_template.as_ref().to_str().ok_or_else(|| {
             YtDlpError::InvalidPath(output_template.as_ref().display().to_string())
         })?;
-        let fps = format!("fps={fps}");
-        let extra_args = extra_args.map(|args| args.join(" ")).unwrap_or_default();
+        let format = options.format_for(output_template.as_ref())?;
+
+        let filter = std::iter::once(format!("fps={fps}"))
+            .chain(options.scale_filter())
+            .collect::<Vec<_>>()
+            .join(",");
+        let encoding_args = options.encoding_args(format);
+
+        // ffmpeg -i input_video.mp4 -vf fps=1/10 -c:v mjpeg -q:v 7 output_%04d.jpg
+        let mut args = vec!["-i", input_str, "-vf", &filter];
+        args.extend(encoding_args.iter().map(String::as_str));
+        args.push(output_str);
+
+        self.run_ffmpeg(&args)
+    }
+
+    fn frames_at(
+        &self,
+        input_path: impl AsRef<Path>,
+        timestamps: &[Duration],
+        output_dir: impl AsRef<Path>,
+        options: &FrameOptions,
+    ) -> Result<Vec<PathBuf>, YtDlpError> {
+        let input_str = input_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| YtDlpError::InvalidPath(input_path.as_ref().display().to_string()))?;
+        let output_dir = output_dir.as_ref();
+        let format = options.format.unwrap_or(ImageFormat::Jpeg);
+        let encoding_args = options.encoding_args(format);
+
+        std::fs::create_dir_all(output_dir)?;
+
+        let mut frames = Vec::with_capacity(timestamps.len());
+        for (i, timestamp) in timestamps.iter().enumerate() {
+            let output_path = output_dir.join(format!("frame_{i:03}.{}", format.extension()));
+            let output_str = output_path
+                .to_str()
+                .ok_or_else(|| YtDlpError::InvalidPath(output_path.display().to_string()))?;
+            // ffmpeg won't overwrite a frame left by an earlier run
+            let _ = std::fs::remove_file(&output_path);
+
+            // seeking before the input jumps to the nearest keyframe, then decodes up to the
+            // exact timestamp
+            let position = format!("{:.3}", timestamp.as_secs_f64());
+            let mut args = vec!["-ss", &position, "-i", input_str, "-frames:v", "1"];
+            let scale_filter = options.scale_filter();
+            if let Some(filter) = &scale_filter {
+                args.extend(["-vf", filter]);
+            }
+            args.extend(encoding_args.iter().map(String::as_str));
+            args.extend(["-update", "1", output_str]);
+
+            self.run_ffmpeg(&args)?;
+
+            // ffmpeg succeeds without writing anything when seeking past the end
+            if !output_path.exists() {
+                return Err(YtDlpError::InvalidTimeRange(format!(
+                    "no frame at {position}s in {input_str}"
+                )));
+            }
+            frames.push(output_path);
+        }
+
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{FakeCommandRunner, ScriptedResponse};
+
+    #[test]
+    fn passes_frame_options_as_separate_args() {
+        let runner = FakeCommandRunner::new();
+        let ytdlp = YtDlp::fake(&runner);
+        let options = FrameOptions {
+            width: Some(320),
+            ..Default::default()
+        };
+
+        ytdlp
+            .extract_frames(
+                "/tmp/in.mp4",
+                "1/10",
+                "/tmp/frames/frame_%04d.webp",
+                &options,
+            )
+            .unwrap();
+
+        let calls = runner.calls();
+        assert!(calls[0].has_arg_pair("-vf", "fps=1/10,scale=320:-2"));
+        assert!(calls[0].has_arg_pair("-c:v", "libwebp"));
+        assert!(calls[0].has_arg_pair("-quality", "80"));
+        assert_eq!(
+            calls[0].args.last().map(String::as_str),
+            Some("/tmp/frames/frame_%04d.webp")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_frame_formats() {
+        let ytdlp = YtDlp::fake(&FakeCommandRunner::new());
+
+        let result = ytdlp.extract_frames(
+            "/tmp/in.mp4",
+            "1",
+            "/tmp/frames/frame_%04d.bmp",
+            &FrameOptions::default(),
+        );
+
+        assert!(matches!(result, Err(YtDlpError::UnsupportedFormat(ext)) if ext == "bmp"));
+    }
+
+    #[test]
+    fn grabs_frames_at_exact_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = FakeCommandRunner::new()
+            .respond(
+                ScriptedResponse::success().creates_file(dir.path().join("frame_000.jpg"), [0]),
+            )
+            .respond(
+                ScriptedResponse::success().creates_file(dir.path().join("frame_001.jpg"), [0]),
+            );
+        let ytdlp = YtDlp::fake(&runner);
+        let options = FrameOptions {
+            width: Some(640),
+            quality: 100,
+            ..Default::default()
+        };
+
+        let frames = ytdlp
+            .frames_at(
+                "/tmp/in.mp4",
+                &[Duration::from_millis(600_250), Duration::from_secs(3723)],
+                dir.path(),
+                &options,
+            )
+            .unwrap();
+
+        assert_eq!(
+            frames,
+            vec![
+                dir.path().join("frame_000.jpg"),
+                dir.path().join("frame_001.jpg")
+            ]
+        );
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].has_arg_pair("-ss", "600.250"));
+        assert!(calls[1].has_arg_pair("-ss", "3723.000"));
+        assert!(calls[0].has_arg_pair("-frames:v", "1"));
+        assert!(calls[0].has_arg_pair("-vf", "scale=640:-2"));
+        assert!(calls[0].has_arg_pair("-q:v", "2"));
+    }
+
+    #[test]
+    fn reports_timestamps_past_the_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let ytdlp = YtDlp::fake(&FakeCommandRunner::new());
+
+        let result = ytdlp.frames_at(
+            Path::new("/tmp/in.mp4"),
+            &[Duration::from_secs(3600)],
+            dir.path(),
+            &FrameOptions::default(),
+        );
 
-        // ffmpeg -i input_video.mp4 -vf fps=1/10 output_%04d.png
-        self.run_ffmpeg(&["-i", input_str, "-vf", &fps, &extra_args, output_str])
+        assert!(matches!(result, Err(YtDlpError::InvalidTimeRange(_))));
     }
 }
@@ -6,10 +6,18 @@ use std::{
     fs::File,
     io::{BufRead, BufReader, Read},
     path::Path,
+    time::Duration,
 };
 use webvtt_parser::OwnedVtt;
 
-use crate::{error::YtDlpError, YtDlp};
+use crate::{error::YtDlpError, TranscriptSegment, YtDlp};
+
+/// Captions running longer than this without sentence punctuation are split anyway, since
+/// YouTube's auto-captions are mostly unpunctuated
+const MAX_SEGMENT_DURATION: Duration = Duration::from_secs(30);
+
+/// Marks a change of speaker in YouTube captions (`&gt;&gt;` before decoding)
+const SPEAKER_CHANGE: &str = ">>";
 
 pub trait VttProcessor {
     /// Reads the entire content of a VTT file into a string.
@@ -47,6 +55,36 @@ pub trait VttProcessor {
     ///
     /// Returns `YtDlpError` if the file cannot be read or parsed.
     fn process_vtt_file<P: AsRef<Path>>(&self, vtt_path: P) -> Result<OwnedVtt, YtDlpError>;
+
+    /// Reads a VTT file into sentence-level transcript segments.
+    ///
+    /// Lines that YouTube's auto-captions repeat across rolling cues are only kept once, inline
+    /// `<c>` and word timing tags are stripped, and the remaining words are merged into
+    /// sentences timed from the word timings where present. Sentences without punctuation are
+    /// split after at most 30 seconds, and at every change of speaker.
+    ///
+    /// # Arguments
+    ///
+    /// * `vtt_path` - The path to the VTT file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the file cannot be read or a cue timing is malformed.
+    fn to_segments<P: AsRef<Path>>(
+        &self,
+        vtt_path: P,
+    ) -> Result<Vec<TranscriptSegment>, YtDlpError>;
+
+    /// Reads a VTT file as plain text, one sentence per line. See [`VttProcessor::to_segments`].
+    ///
+    /// # Arguments
+    ///
+    /// * `vtt_path` - The path to the VTT file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the file cannot be read or a cue timing is malformed.
+    fn to_plain_text<P: AsRef<Path>>(&self, vtt_path: P) -> Result<String, YtDlpError>;
 }
 
 impl VttProcessor for YtDlp {
@@ -82,4 +120,377 @@ impl VttProcessor for YtDlp {
         let content = self.read_vtt_file(vtt_path)?;
         OwnedVtt::parse(&content).map_err(|e| YtDlpError::VttReadError(e.to_string()))
     }
+
+    fn to_segments<P: AsRef<Path>>(
+        &self,
+        vtt_path: P,
+    ) -> Result<Vec<TranscriptSegment>, YtDlpError> {
+        let content = self.read_vtt_file(vtt_path)?;
+        let cues = parse_cues(&content)?;
+        Ok(merge_into_sentences(caption_words(&cues)))
+    }
+
+    fn to_plain_text<P: AsRef<Path>>(&self, vtt_path: P) -> Result<String, YtDlpError> {
+        Ok(TranscriptSegment::join_text(&self.to_segments(vtt_path)?))
+    }
+}
+
+/// A cue as written in the file, text lines still carrying their inline tags.
+#[derive(Debug)]
+struct Cue {
+    start: Duration,
+    end: Duration,
+    lines: Vec<String>,
+}
+
+#[derive(Debug)]
+struct TimedWord {
+    start: Duration,
+    end: Duration,
+    text: String,
+}
+
+/// Parses the cues of a VTT file, skipping the header, notes, styles and cue identifiers.
+///
+/// Cue text runs up to the next empty line, so the whitespace-only lines YouTube puts in
+/// its rolling cues don't end a cue early.
+fn parse_cues(content: &str) -> Result<Vec<Cue>, YtDlpError> {
+    let mut cues = Vec::new();
+    let mut current: Option<Cue> = None;
+
+    for line in content.lines() {
+        if let Some((start, rest)) = line.split_once("-->") {
+            cues.extend(current.take());
+
+            let end = rest.split_whitespace().next().unwrap_or_default();
+            let (Some(start), Some(end)) = (parse_vtt_timestamp(start), parse_vtt_timestamp(end))
+            else {
+                return Err(YtDlpError::VttReadError(format!(
+                    "invalid cue timing: '{line}'"
+                )));
+            };
+
+            current = Some(Cue {
+                start,
+                end,
+                lines: Vec::new(),
+            });
+        } else if line.is_empty() {
+            cues.extend(current.take());
+        } else if let Some(cue) = current.as_mut() {
+            cue.lines.push(line.to_string());
+        }
+    }
+    cues.extend(current);
+
+    Ok(cues)
+}
+
+/// Parses `HH:MM:SS.mmm` or `MM:SS.mmm`
+fn parse_vtt_timestamp(value: &str) -> Option<Duration> {
+    let (clock, millis) = value.trim().split_once('.')?;
+    if millis.len() != 3 {
+        return None;
+    }
+    let millis = millis.parse::<u64>().ok()?;
+
+    let parts = clock
+        .split(':')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let secs = match parts[..] {
+        [hours, minutes, secs] => hours * 3600 + minutes * 60 + secs,
+        [minutes, secs] => minutes * 60 + secs,
+        _ => return None,
+    };
+
+    Some(Duration::from_millis(secs * 1000 + millis))
+}
+
+/// Collects the words spoken across all cues, each only once.
+///
+/// YouTube's auto-captions show two lines at a time and roll them up: every cue repeats the
+/// previous cue's last line above the new one, with short transitional cues in between that
+/// repeat it alone. Leading lines that repeat the previous cue's trailing lines are dropped.
+fn caption_words(cues: &[Cue]) -> Vec<TimedWord> {
+    let mut words = Vec::new();
+    let mut previous: Vec<String> = Vec::new();
+
+    for cue in cues {
+        let lines = cue
+            .lines
+            .iter()
+            .map(|line| (line.as_str(), strip_tags(line)))
+            .filter(|(_, plain)| !plain.is_empty())
+            .collect::<Vec<_>>();
+        let plain = lines
+            .iter()
+            .map(|(_, plain)| plain.clone())
+            .collect::<Vec<_>>();
+
+        let repeated = (1..=plain.len().min(previous.len()))
+            .rev()
+            .find(|&n| previous[previous.len() - n..] == plain[..n])
+            .unwrap_or(0);
+
+        let text = lines[repeated..]
+            .iter()
+            .map(|(raw, _)| *raw)
+            .collect::<Vec<_>>()
+            .join(" ");
+        words.extend(timed_words(&text, cue.start, cue.end));
+
+        previous = plain;
+    }
+
+    words
+}
+
+/// Removes inline tags and collapses whitespace, e.g. `hi<00:00:01.000><c> there</c>` to `hi there`.
+fn strip_tags(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut in_tag = false;
+
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Splits cue text into words, timed by the inline `<HH:MM:SS.mmm>` tags that precede them.
+///
+/// Words without a timing tag start with the word before them, and cue text without any is
+/// spread evenly across the cue.
+fn timed_words(text: &str, start: Duration, end: Duration) -> Vec<TimedWord> {
+    let mut words: Vec<(Option<Duration>, String)> = Vec::new();
+    let mut pending_timestamp = None;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let (chunk, tag, remainder) = match rest.find('<') {
+            Some(open) => match rest[open..].find('>') {
+                Some(close) => (
+                    &rest[..open],
+                    Some(&rest[open + 1..open + close]),
+                    &rest[open + close + 1..],
+                ),
+                None => (&rest[..open], None, ""),
+            },
+            None => (rest, None, ""),
+        };
+
+        for word in chunk.split_whitespace() {
+            words.push((pending_timestamp.take(), decode_entities(word)));
+        }
+        if let Some(timestamp) = tag.and_then(parse_vtt_timestamp) {
+            pending_timestamp = Some(timestamp.clamp(start, end.max(start)));
+        }
+        rest = remainder;
+    }
+
+    let count = words.len() as u32;
+    let has_timings = words.iter().any(|(timestamp, _)| timestamp.is_some());
+    let mut starts = Vec::with_capacity(words.len());
+
+    for (i, (timestamp, _)) in words.iter().enumerate() {
+        let word_start = match timestamp {
+            Some(timestamp) => *timestamp,
+            None if has_timings => starts.last().copied().unwrap_or(start),
+            None => start + end.saturating_sub(start) * i as u32 / count,
+        };
+        starts.push(word_start);
+    }
+
+    words
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, text))| TimedWord {
+            start: starts[i],
+            end: starts.get(i + 1).copied().unwrap_or(end.max(starts[i])),
+            text,
+        })
+        .collect()
+}
+
+fn decode_entities(word: &str) -> String {
+    word.replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&nbsp;", " ")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')', ']', '\u{201d}', '\u{2019}'])
+        .ends_with(['.', '?', '!'])
+}
+
+/// Merges words into sentences, also splitting at speaker changes and overly long runs.
+fn merge_into_sentences(words: Vec<TimedWord>) -> Vec<TranscriptSegment> {
+    let mut segments = Vec::new();
+    let mut sentence: Vec<TimedWord> = Vec::new();
+
+    let flush = |sentence: &mut Vec<TimedWord>, segments: &mut Vec<TranscriptSegment>| {
+        if let (Some(first), Some(last)) = (sentence.first(), sentence.last()) {
+            let text = sentence
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            segments.push(TranscriptSegment::new(first.start, last.end, text));
+        }
+        sentence.clear();
+    };
+
+    for word in words {
+        if word.text == SPEAKER_CHANGE {
+            flush(&mut sentence, &mut segments);
+            continue;
+        }
+
+        if sentence
+            .first()
+            .is_some_and(|first| word.end.saturating_sub(first.start) > MAX_SEGMENT_DURATION)
+        {
+            flush(&mut sentence, &mut segments);
+        }
+
+        let is_last = ends_sentence(&word.text);
+        sentence.push(word);
+        if is_last {
+            flush(&mut sentence, &mut segments);
+        }
+    }
+    flush(&mut sentence, &mut segments);
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::FakeCommandRunner;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// Trimmed from a YouTube auto-caption download
+    const ROLLING_CAPTIONS: &str = "WEBVTT
+Kind: captions
+Language: en
+
+00:00:00.000 --> 00:00:02.879 align:start position:0%
+ 
+order<00:00:00.480><c> members</c><00:00:01.040><c> please</c><00:00:01.520><c> take</c>
+
+00:00:02.879 --> 00:00:02.889 align:start position:0%
+order members please take
+ 
+
+00:00:02.889 --> 00:00:05.269 align:start position:0%
+order members please take
+your<00:00:03.120><c> seats.</c><00:00:03.600><c> &gt;&gt;</c><00:00:04.000><c> thank</c><00:00:04.400><c> you</c>
+
+00:00:05.269 --> 00:00:05.279 align:start position:0%
+your seats. &gt;&gt; thank you
+ 
+
+00:00:05.279 --> 00:00:07.000 align:start position:0%
+your seats. &gt;&gt; thank you
+speaker.
+";
+
+    #[test]
+    fn parses_cues_and_timestamps() {
+        let cues = parse_cues(ROLLING_CAPTIONS).unwrap();
+
+        assert_eq!(cues.len(), 5);
+        assert_eq!(cues[2].start, millis(2_889));
+        assert_eq!(cues[2].end, millis(5_269));
+        assert_eq!(cues[2].lines.len(), 2);
+        assert_eq!(parse_vtt_timestamp("01:02:03.004"), Some(millis(3_723_004)));
+        assert_eq!(parse_vtt_timestamp("02:03.004"), Some(millis(123_004)));
+        assert_eq!(parse_vtt_timestamp("02:03"), None);
+        assert!(parse_cues("WEBVTT\n\n00:01.000 --> soon\nhello\n").is_err());
+    }
+
+    #[test]
+    fn drops_rolling_duplicates_and_tags() {
+        let words = caption_words(&parse_cues(ROLLING_CAPTIONS).unwrap());
+        let text = words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        assert_eq!(
+            text,
+            "order members please take your seats. >> thank you speaker."
+        );
+        assert_eq!(words[1].start, millis(480));
+        assert_eq!(words[1].end, millis(1_040));
+        assert_eq!(words[3].end, millis(2_879));
+    }
+
+    #[test]
+    fn merges_words_into_sentences() {
+        let dir = tempfile::tempdir().unwrap();
+        let vtt_path = dir.path().join("captions.en.vtt");
+        std::fs::write(&vtt_path, ROLLING_CAPTIONS).unwrap();
+        let ytdlp = YtDlp::fake(&FakeCommandRunner::new());
+
+        let segments = ytdlp.to_segments(&vtt_path).unwrap();
+
+        assert_eq!(
+            segments,
+            vec![
+                TranscriptSegment::new(
+                    millis(0),
+                    millis(3_600),
+                    "order members please take your seats."
+                ),
+                TranscriptSegment::new(millis(4_000), millis(7_000), "thank you speaker."),
+            ]
+        );
+        assert_eq!(
+            ytdlp.to_plain_text(&vtt_path).unwrap(),
+            "order members please take your seats.\nthank you speaker."
+        );
+    }
+
+    #[test]
+    fn spreads_untimed_words_across_the_cue() {
+        let words = timed_words("one two three four", millis(1_000), millis(3_000));
+
+        assert_eq!(
+            words.iter().map(|word| word.start).collect::<Vec<_>>(),
+            vec![millis(1_000), millis(1_500), millis(2_000), millis(2_500)]
+        );
+        assert_eq!(words[3].end, millis(3_000));
+    }
+
+    #[test]
+    fn splits_unpunctuated_captions() {
+        let words = (0..40)
+            .map(|i| TimedWord {
+                start: Duration::from_secs(i),
+                end: Duration::from_secs(i + 1),
+                text: "word".to_string(),
+            })
+            .collect();
+
+        let segments = merge_into_sentences(words);
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].end, Duration::from_secs(30));
+        assert_eq!(segments[1].start, Duration::from_secs(30));
+    }
 }
@@ -0,0 +1,214 @@
+//! # runner
+//!
+//! Abstraction over spawning external programs (`yt-dlp`, `ffmpeg`, `ffprobe`).
+//!
+//! `YtDlp` spawns every command through a [`CommandRunner`], which defaults to
+//! [`SystemCommandRunner`]. Swapping it for a [`FakeCommandRunner`] (behind the `test-utils`
+//! feature) makes it possible to test retries, error classification and argument
+//! construction without network access or installed binaries.
+
+use std::fmt::Debug;
+use std::path::Path;
+use std::process::Command;
+
+/// The captured result of running a command to completion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code, `None` if the process was terminated by a signal
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.status == Some(0)
+    }
+}
+
+/// Runs external programs on behalf of `YtDlp`.
+pub trait CommandRunner: Debug + Send + Sync {
+    /// Runs `program` with `args` to completion, capturing its output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `std::io::Error` if the program could not be spawned, with
+    /// [`std::io::ErrorKind::NotFound`] if it does not exist.
+    fn run(&self, program: &Path, args: &[&str]) -> std::io::Result<CommandOutput>;
+}
+
+/// Runs commands as child processes via [`std::process::Command`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &Path, args: &[&str]) -> std::io::Result<CommandOutput> {
+        let output = Command::new(program).args(args).output()?;
+
+        Ok(CommandOutput {
+            status: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into(),
+            stderr: String::from_utf8_lossy(&output.stderr).into(),
+        })
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+pub use fake::{FakeCommandRunner, RecordedCommand, ScriptedResponse};
+
+#[cfg(any(test, feature = "test-utils"))]
+mod fake {
+    use std::collections::VecDeque;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+
+    use super::{CommandOutput, CommandRunner};
+
+    /// A command invocation captured by [`FakeCommandRunner`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct RecordedCommand {
+        pub program: PathBuf,
+        pub args: Vec<String>,
+    }
+
+    impl RecordedCommand {
+        /// Returns `true` if `flag` is immediately followed by `value` in the arguments.
+        pub fn has_arg_pair(&self, flag: &str, value: &str) -> bool {
+            self.args.windows(2).any(|w| w[0] == flag && w[1] == value)
+        }
+    }
+
+    /// A scripted result for a single command invocation.
+    #[derive(Debug, Clone, Default)]
+    pub struct ScriptedResponse {
+        output: CommandOutput,
+        files: Vec<(PathBuf, Vec<u8>)>,
+        not_found: bool,
+    }
+
+    impl ScriptedResponse {
+        /// A successful (exit code 0) response with no output.
+        pub fn success() -> Self {
+            ScriptedResponse {
+                output: CommandOutput {
+                    status: Some(0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        /// A failed response with the given exit code.
+        pub fn failure(status: i32) -> Self {
+            ScriptedResponse {
+                output: CommandOutput {
+                    status: Some(status),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        /// Simulates a program that is not installed.
+        pub fn not_found() -> Self {
+            ScriptedResponse {
+                not_found: true,
+                ..Default::default()
+            }
+        }
+
+        pub fn stdout(mut self, stdout: impl Into<String>) -> Self {
+            self.output.stdout = stdout.into();
+            self
+        }
+
+        pub fn stderr(mut self, stderr: impl Into<String>) -> Self {
+            self.output.stderr = stderr.into();
+            self
+        }
+
+        /// Writes a fixture file when the command runs, standing in for whatever the
+        /// real program would have produced (downloads, chunks, thumbnails...).
+        pub fn creates_file(
+            mut self,
+            path: impl Into<PathBuf>,
+            contents: impl Into<Vec<u8>>,
+        ) -> Self {
+            self.files.push((path.into(), contents.into()));
+            self
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct FakeState {
+        responses: VecDeque<ScriptedResponse>,
+        calls: Vec<RecordedCommand>,
+    }
+
+    /// A [`CommandRunner`] that records invocations and replays scripted responses in order.
+    ///
+    /// Once the script is exhausted every further command succeeds with no output.
+    /// Clones share the same script and recorded calls, so keep a clone around to make
+    /// assertions after handing one to `YtDlp`.
+    #[derive(Debug, Clone, Default)]
+    pub struct FakeCommandRunner {
+        state: Arc<Mutex<FakeState>>,
+    }
+
+    impl FakeCommandRunner {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Queues the response for the next unanswered invocation.
+        pub fn respond(self, response: ScriptedResponse) -> Self {
+            self.state
+                .lock()
+                .expect("FakeCommandRunner state poisoned")
+                .responses
+                .push_back(response);
+            self
+        }
+
+        /// All invocations so far, in order.
+        pub fn calls(&self) -> Vec<RecordedCommand> {
+            self.state
+                .lock()
+                .expect("FakeCommandRunner state poisoned")
+                .calls
+                .clone()
+        }
+    }
+
+    impl CommandRunner for FakeCommandRunner {
+        fn run(&self, program: &Path, args: &[&str]) -> std::io::Result<CommandOutput> {
+            let response = {
+                let mut state = self.state.lock().expect("FakeCommandRunner state poisoned");
+                state.calls.push(RecordedCommand {
+                    program: program.to_path_buf(),
+                    args: args.iter().map(|a| a.to_string()).collect(),
+                });
+                state
+                    .responses
+                    .pop_front()
+                    .unwrap_or_else(ScriptedResponse::success)
+            };
+
+            if response.not_found {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("{} not found", program.display()),
+                ));
+            }
+
+            for (path, contents) in &response.files {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, contents)?;
+            }
+
+            Ok(response.output)
+        }
+    }
+}
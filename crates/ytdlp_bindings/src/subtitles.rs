@@ -0,0 +1,327 @@
+//! # subtitles
+//!
+//! Renders timed transcript segments as WebVTT or SRT captions, wrapping long sentences
+//! into cues short enough to read while the video plays.
+
+use std::{path::Path, time::Duration};
+
+use crate::{time_range::format_timestamp, TranscriptSegment, YtDlpError};
+
+/// Caption file formats supported by [`SubtitleWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubtitleFormat {
+    WebVtt,
+    Srt,
+}
+
+impl SubtitleFormat {
+    /// The usual file extension, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SubtitleFormat::WebVtt => "vtt",
+            SubtitleFormat::Srt => "srt",
+        }
+    }
+
+    /// The MIME type to serve the captions with
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            SubtitleFormat::WebVtt => "text/vtt; charset=utf-8",
+            SubtitleFormat::Srt => "application/x-subrip; charset=utf-8",
+        }
+    }
+
+    /// Picks the format matching a file extension.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "vtt" => Some(SubtitleFormat::WebVtt),
+            "srt" => Some(SubtitleFormat::Srt),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes [`TranscriptSegment`]s to caption files.
+///
+/// Segments are wrapped into lines of at most [`SubtitleWriter::with_max_line_chars`] characters
+/// and grouped into cues of at most [`SubtitleWriter::with_max_lines`] lines, each cue getting
+/// a share of its segment's time proportional to its length. No cue stays on screen longer
+/// than [`SubtitleWriter::with_max_cue_duration`].
+///
+/// The defaults (42 characters, 2 lines, 7 seconds) follow common broadcast captioning guidelines.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use ytdlp_bindings::{SubtitleFormat, SubtitleWriter, TranscriptSegment};
+///
+/// let segments = vec![TranscriptSegment::new(
+///     Duration::ZERO,
+///     Duration::from_secs(2),
+///     "Order, order!",
+/// )];
+/// let captions = SubtitleWriter::new(SubtitleFormat::Srt).render(&segments);
+///
+/// assert_eq!(captions, "1\n00:00:00,000 --> 00:00:02,000\nOrder, order!\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubtitleWriter {
+    format: SubtitleFormat,
+    max_line_chars: usize,
+    max_lines: usize,
+    max_cue_duration: Duration,
+}
+
+/// A caption as shown on screen
+#[derive(Debug, PartialEq, Eq)]
+struct Cue {
+    start: Duration,
+    end: Duration,
+    lines: Vec<String>,
+}
+
+impl SubtitleWriter {
+    pub fn new(format: SubtitleFormat) -> Self {
+        SubtitleWriter {
+            format,
+            max_line_chars: 42,
+            max_lines: 2,
+            max_cue_duration: Duration::from_secs(7),
+        }
+    }
+
+    /// Longest line, in characters. Words longer than this get a line of their own.
+    pub fn with_max_line_chars(mut self, max_line_chars: usize) -> Self {
+        self.max_line_chars = max_line_chars.max(1);
+        self
+    }
+
+    /// Most lines shown at once
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines.max(1);
+        self
+    }
+
+    /// Longest time a cue stays on screen
+    pub fn with_max_cue_duration(mut self, max_cue_duration: Duration) -> Self {
+        self.max_cue_duration = max_cue_duration;
+        self
+    }
+
+    pub fn format(&self) -> SubtitleFormat {
+        self.format
+    }
+
+    /// Renders `segments` as a caption file.
+    pub fn render(&self, segments: &[TranscriptSegment]) -> String {
+        let cues = segments
+            .iter()
+            .flat_map(|segment| self.layout(segment))
+            .collect::<Vec<_>>();
+
+        let mut output = match self.format {
+            SubtitleFormat::WebVtt => String::from("WEBVTT\n"),
+            SubtitleFormat::Srt => String::new(),
+        };
+
+        for (i, cue) in cues.iter().enumerate() {
+            match self.format {
+                SubtitleFormat::WebVtt => {
+                    output.push('\n');
+                    output.push_str(&format!(
+                        "{} --> {}\n",
+                        format_timestamp(cue.start),
+                        format_timestamp(cue.end)
+                    ));
+                    for line in &cue.lines {
+                        output.push_str(&escape_vtt(line));
+                        output.push('\n');
+                    }
+                }
+                SubtitleFormat::Srt => {
+                    if i > 0 {
+                        output.push('\n');
+                    }
+                    output.push_str(&format!(
+                        "{}\n{} --> {}\n",
+                        i + 1,
+                        format_srt_timestamp(cue.start),
+                        format_srt_timestamp(cue.end)
+                    ));
+                    for line in &cue.lines {
+                        output.push_str(line);
+                        output.push('\n');
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Renders `segments` and writes them to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the file cannot be written.
+    pub fn write(
+        &self,
+        segments: &[TranscriptSegment],
+        path: impl AsRef<Path>,
+    ) -> Result<(), YtDlpError> {
+        std::fs::write(path, self.render(segments))?;
+        Ok(())
+    }
+
+    /// Splits a segment into cues, timing each by its share of the segment's characters.
+    fn layout(&self, segment: &TranscriptSegment) -> Vec<Cue> {
+        let lines = wrap(&segment.text, self.max_line_chars);
+        let total_chars = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .sum::<usize>()
+            .max(1) as u32;
+
+        let mut cues = Vec::new();
+        let mut elapsed_chars = 0;
+
+        for lines in lines.chunks(self.max_lines) {
+            let chars = lines.iter().map(|line| line.chars().count()).sum::<usize>() as u32;
+            let start = segment.start + segment.duration() * elapsed_chars / total_chars;
+            elapsed_chars += chars;
+            let end = segment.start + segment.duration() * elapsed_chars / total_chars;
+
+            cues.push(Cue {
+                start,
+                end: end.min(start + self.max_cue_duration),
+                lines: lines.to_vec(),
+            });
+        }
+
+        cues
+    }
+}
+
+/// Greedily wraps `text` into lines of at most `max_chars` characters.
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let needed = if line.is_empty() {
+            word.chars().count()
+        } else {
+            line.chars().count() + 1 + word.chars().count()
+        };
+
+        if needed > max_chars && !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Formats a duration as `HH:MM:SS,mmm`
+fn format_srt_timestamp(duration: Duration) -> String {
+    format_timestamp(duration).replacen('.', ",", 1)
+}
+
+/// Escapes the characters WebVTT reserves for markup
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        assert_eq!(
+            wrap("the quick brown fox jumps over the lazy dog", 15),
+            vec!["the quick brown", "fox jumps over", "the lazy dog"]
+        );
+        assert_eq!(
+            wrap("a supercalifragilistic word", 10),
+            vec!["a", "supercalifragilistic", "word"]
+        );
+    }
+
+    #[test]
+    fn splits_long_segments_into_timed_cues() {
+        let writer = SubtitleWriter::new(SubtitleFormat::WebVtt)
+            .with_max_line_chars(15)
+            .with_max_lines(2);
+        // 15 + 14 characters in the first cue, 12 in the second
+        let segment = TranscriptSegment::new(
+            millis(1_000),
+            millis(42_000),
+            "the quick brown fox jumps over the lazy dog",
+        );
+
+        let cues = writer.layout(&segment);
+
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start, millis(1_000));
+        // capped from 30s
+        assert_eq!(cues[0].end, millis(8_000));
+        assert_eq!(cues[1].start, millis(30_000));
+        assert_eq!(cues[1].end, millis(37_000));
+        assert_eq!(cues[1].lines, vec!["the lazy dog"]);
+    }
+
+    #[test]
+    fn renders_webvtt() {
+        let segments = vec![
+            TranscriptSegment::new(millis(0), millis(2_500), "Order, order!"),
+            TranscriptSegment::new(
+                millis(3_723_004),
+                millis(3_725_000),
+                "Members <inaudible> & guests.",
+            ),
+        ];
+
+        assert_eq!(
+            SubtitleWriter::new(SubtitleFormat::WebVtt).render(&segments),
+            "WEBVTT\n\
+             \n\
+             00:00:00.000 --> 00:00:02.500\n\
+             Order, order!\n\
+             \n\
+             01:02:03.004 --> 01:02:05.000\n\
+             Members &lt;inaudible&gt; &amp; guests.\n"
+        );
+    }
+
+    #[test]
+    fn renders_srt() {
+        let segments = vec![
+            TranscriptSegment::new(millis(0), millis(2_500), "Order, order!"),
+            TranscriptSegment::new(millis(3_000), millis(5_000), "Please be seated."),
+        ];
+
+        assert_eq!(
+            SubtitleWriter::new(SubtitleFormat::Srt).render(&segments),
+            "1\n\
+             00:00:00,000 --> 00:00:02,500\n\
+             Order, order!\n\
+             \n\
+             2\n\
+             00:00:03,000 --> 00:00:05,000\n\
+             Please be seated.\n"
+        );
+    }
+}
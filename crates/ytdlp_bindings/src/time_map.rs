@@ -0,0 +1,217 @@
+//! # time_map
+//!
+//! Maps positions in audio with spans cut out of it back to positions in the original,
+//! so that timestamps taken from the shortened audio still point at the right moment in the video.
+
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::YtDlpError;
+
+/// A stretch of the original audio that was kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct KeptSpan {
+    /// Where the span starts in the original audio
+    #[serde(with = "crate::duration_secs")]
+    original_start: Duration,
+    /// Where the span starts in the shortened audio
+    #[serde(with = "crate::duration_secs")]
+    trimmed_start: Duration,
+    #[serde(with = "crate::duration_secs")]
+    duration: Duration,
+}
+
+/// Converts positions in shortened audio back to positions in the original.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeMap {
+    #[serde(with = "crate::duration_secs")]
+    original_duration: Duration,
+    kept: Vec<KeptSpan>,
+}
+
+impl TimeMap {
+    /// A map for audio that was not shortened at all.
+    pub fn identity(duration: Duration) -> Self {
+        TimeMap::from_removed(duration, &[])
+    }
+
+    /// Builds the map for audio of `original_duration` with the `removed` `(start, end)` spans
+    /// cut out. Spans may be unordered or overlap, and are clamped to the audio's duration.
+    pub(crate) fn from_removed(
+        original_duration: Duration,
+        removed: &[(Duration, Duration)],
+    ) -> Self {
+        let mut removed = removed
+            .iter()
+            .map(|&(start, end)| (start.min(original_duration), end.min(original_duration)))
+            .filter(|(start, end)| start < end)
+            .collect::<Vec<_>>();
+        removed.sort();
+
+        let mut kept = Vec::with_capacity(removed.len() + 1);
+        let mut original_start = Duration::ZERO;
+        let mut trimmed_start = Duration::ZERO;
+
+        for (start, end) in removed
+            .into_iter()
+            .chain([(original_duration, original_duration)])
+        {
+            if start > original_start {
+                let duration = start - original_start;
+                kept.push(KeptSpan {
+                    original_start,
+                    trimmed_start,
+                    duration,
+                });
+                trimmed_start += duration;
+            }
+            original_start = original_start.max(end);
+        }
+
+        TimeMap {
+            original_duration,
+            kept,
+        }
+    }
+
+    /// Converts a position in the shortened audio to the matching position in the original.
+    ///
+    /// A position on the boundary of a removed span maps to the end of that span, i.e. to where
+    /// the audio resumes. Positions past the end of the shortened audio are extrapolated.
+    pub fn to_original(&self, trimmed: Duration) -> Duration {
+        let idx = self
+            .kept
+            .partition_point(|span| span.trimmed_start <= trimmed)
+            .saturating_sub(1);
+
+        match self.kept.get(idx) {
+            Some(span) => span.original_start + trimmed.saturating_sub(span.trimmed_start),
+            None => trimmed,
+        }
+    }
+
+    /// Duration of the original audio
+    pub fn original_duration(&self) -> Duration {
+        self.original_duration
+    }
+
+    /// Duration of the shortened audio
+    pub fn trimmed_duration(&self) -> Duration {
+        self.kept.iter().map(|span| span.duration).sum()
+    }
+
+    /// Total duration cut out of the original audio
+    pub fn removed_duration(&self) -> Duration {
+        self.original_duration
+            .saturating_sub(self.trimmed_duration())
+    }
+
+    /// The `(start, end)` spans of the original audio that were cut out, in order
+    pub fn removed_spans(&self) -> Vec<(Duration, Duration)> {
+        let mut spans = Vec::new();
+        let mut position = Duration::ZERO;
+
+        for span in &self.kept {
+            if span.original_start > position {
+                spans.push((position, span.original_start));
+            }
+            position = span.original_start + span.duration;
+        }
+        if self.original_duration > position {
+            spans.push((position, self.original_duration));
+        }
+
+        spans
+    }
+
+    /// Writes the map as JSON, typically beside the shortened audio.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the map cannot be serialized or written.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), YtDlpError> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Reads a map written by [`TimeMap::write`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the map cannot be read or parsed.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, YtDlpError> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn identity_map_leaves_positions_unchanged() {
+        let map = TimeMap::identity(secs(100));
+
+        assert_eq!(map.to_original(secs(42)), secs(42));
+        assert_eq!(map.removed_duration(), Duration::ZERO);
+        assert!(map.removed_spans().is_empty());
+    }
+
+    #[test]
+    fn maps_positions_across_removed_spans() {
+        // keeps 0-100, 400-500 and 600-1000
+        let map = TimeMap::from_removed(
+            secs(1000),
+            &[(secs(500), secs(600)), (secs(100), secs(400))],
+        );
+
+        assert_eq!(map.trimmed_duration(), secs(600));
+        assert_eq!(map.removed_duration(), secs(400));
+        assert_eq!(map.to_original(secs(50)), secs(50));
+        assert_eq!(map.to_original(secs(100)), secs(400));
+        assert_eq!(map.to_original(secs(150)), secs(450));
+        assert_eq!(map.to_original(secs(250)), secs(650));
+        assert_eq!(map.to_original(secs(600)), secs(1000));
+    }
+
+    #[test]
+    fn handles_leading_trailing_and_overlapping_spans() {
+        let map = TimeMap::from_removed(
+            secs(1000),
+            &[
+                (secs(0), secs(30)),
+                (secs(200), secs(300)),
+                (secs(250), secs(350)),
+                (secs(900), secs(1200)),
+            ],
+        );
+
+        assert_eq!(map.to_original(Duration::ZERO), secs(30));
+        assert_eq!(map.to_original(secs(170)), secs(350));
+        assert_eq!(
+            map.removed_spans(),
+            vec![
+                (secs(0), secs(30)),
+                (secs(200), secs(350)),
+                (secs(900), secs(1000))
+            ]
+        );
+        assert_eq!(map.trimmed_duration(), secs(720));
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("time_map.json");
+        let map = TimeMap::from_removed(secs(1000), &[(secs(100), secs(400))]);
+
+        map.write(&path).unwrap();
+
+        assert_eq!(TimeMap::read(&path).unwrap(), map);
+    }
+}
@@ -0,0 +1,147 @@
+//! # time_range
+//!
+//! A typed `[start, end)` range within a video, used for partial (section) downloads.
+
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use crate::YtDlpError;
+
+/// A time range within a video, e.g. `00:10:00-00:25:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeRange {
+    start: Duration,
+    end: Duration,
+}
+
+impl TimeRange {
+    /// Creates a new range.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`YtDlpError::InvalidTimeRange`] if `start` is not before `end`.
+    pub fn new(start: Duration, end: Duration) -> Result<Self, YtDlpError> {
+        if start >= end {
+            return Err(YtDlpError::InvalidTimeRange(format!(
+                "start ({}) must be before end ({})",
+                format_timestamp(start),
+                format_timestamp(end)
+            )));
+        }
+
+        Ok(TimeRange { start, end })
+    }
+
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Formats the range as a yt-dlp `--download-sections` value, e.g. `*00:10:00.000-00:25:00.000`
+    pub(crate) fn to_section_arg(self) -> String {
+        format!("*{self}")
+    }
+}
+
+impl Display for TimeRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            format_timestamp(self.start),
+            format_timestamp(self.end)
+        )
+    }
+}
+
+impl FromStr for TimeRange {
+    type Err = YtDlpError;
+
+    /// Parses ranges such as `"00:10:00-00:25:00"`, `"10:00-25:00.5"` or `"600-1500"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| YtDlpError::InvalidTimeRange(format!("expected 'start-end': {s}")))?;
+
+        TimeRange::new(parse_timestamp(start)?, parse_timestamp(end)?)
+    }
+}
+
+/// Formats a duration as `HH:MM:SS.mmm`
+pub(crate) fn format_timestamp(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60,
+        duration.subsec_millis()
+    )
+}
+
+/// Parses `[[HH:]MM:]SS[.fff]` into a duration
+pub(crate) fn parse_timestamp(value: &str) -> Result<Duration, YtDlpError> {
+    let invalid = || YtDlpError::InvalidTimeRange(format!("invalid timestamp: '{value}'"));
+
+    let parts = value.trim().split(':').collect::<Vec<_>>();
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(invalid());
+    }
+
+    let (seconds, rest) = parts.split_last().ok_or_else(invalid)?;
+    let seconds = seconds.parse::<f64>().map_err(|_| invalid())?;
+    let minutes = rest.iter().try_fold(0_u64, |acc, part| {
+        part.parse::<u64>()
+            .map(|v| acc * 60 + v)
+            .map_err(|_| invalid())
+    })?;
+
+    Duration::try_from_secs_f64(minutes as f64 * 60.0 + seconds).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_and_inverted_ranges() {
+        let ten = Duration::from_secs(10);
+        assert!(TimeRange::new(ten, ten).is_err());
+        assert!(TimeRange::new(ten, Duration::from_secs(5)).is_err());
+    }
+
+    #[test]
+    fn formats_section_arg() {
+        let range =
+            TimeRange::new(Duration::from_secs(600), Duration::from_millis(1_500_250)).unwrap();
+
+        assert_eq!(range.to_section_arg(), "*00:10:00.000-00:25:00.250");
+        assert_eq!(range.duration(), Duration::from_millis(900_250));
+    }
+
+    #[test]
+    fn parses_ranges() {
+        let expected = TimeRange::new(Duration::from_secs(600), Duration::from_secs(1500)).unwrap();
+
+        assert_eq!("00:10:00-00:25:00".parse::<TimeRange>().unwrap(), expected);
+        assert_eq!("10:00-25:00".parse::<TimeRange>().unwrap(), expected);
+        assert_eq!("600-1500".parse::<TimeRange>().unwrap(), expected);
+        assert_eq!(
+            "1:02:03.5-1:02:04".parse::<TimeRange>().unwrap().start(),
+            Duration::from_millis(3_723_500)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        assert!("00:10:00".parse::<TimeRange>().is_err());
+        assert!("aa:10-00:20".parse::<TimeRange>().is_err());
+        assert!("1:2:3:4-1:2:3:5".parse::<TimeRange>().is_err());
+    }
+}
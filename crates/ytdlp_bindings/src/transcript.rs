@@ -0,0 +1,72 @@
+//! # transcript
+//!
+//! Timed transcript segments, shared by caption (VTT) and speech-to-text output so that
+//! either source can be stored, searched and displayed the same way.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::TimeMap;
+
+/// A stretch of transcribed speech, usually a sentence, with its position in the video.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    #[serde(with = "crate::duration_secs")]
+    pub start: Duration,
+    #[serde(with = "crate::duration_secs")]
+    pub end: Duration,
+    pub text: String,
+}
+
+impl TranscriptSegment {
+    pub fn new(start: Duration, end: Duration, text: impl Into<String>) -> Self {
+        TranscriptSegment {
+            start,
+            end,
+            text: text.into(),
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Moves a segment timed relative to an audio chunk to its position in the shortened
+    /// audio the chunk was cut from, then back to the original through `time_map`.
+    pub fn to_original(&self, chunk_start: Duration, time_map: &TimeMap) -> Self {
+        TranscriptSegment {
+            start: time_map.to_original(chunk_start + self.start),
+            end: time_map.to_original(chunk_start + self.end),
+            text: self.text.clone(),
+        }
+    }
+
+    /// Joins segments into plain text, one segment per line.
+    pub fn join_text(segments: &[TranscriptSegment]) -> String {
+        segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_chunk_relative_segments_to_the_original() {
+        let secs = Duration::from_secs;
+        // 100-400 was cut out of the original
+        let time_map = TimeMap::from_removed(secs(1000), &[(secs(100), secs(400))]);
+        let segment = TranscriptSegment::new(secs(10), secs(20), "Order, order.");
+
+        let mapped = segment.to_original(secs(90), &time_map);
+
+        assert_eq!(mapped.start, secs(400));
+        assert_eq!(mapped.end, secs(410));
+        assert_eq!(mapped.text, "Order, order.");
+    }
+}
@@ -1,11 +1,12 @@
 use std::fmt::Debug;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::YtDlpError;
+use crate::runner::{CommandOutput, CommandRunner, SystemCommandRunner};
+use crate::{TimeRange, YtDlpError};
 
-#[cfg(feature = "yt-dlp-vendored")]
 include!(concat!(env!("OUT_DIR"), "/generated.rs"));
 
 /// The main struct for interacting with yt-dlp.
@@ -16,8 +17,14 @@ include!(concat!(env!("OUT_DIR"), "/generated.rs"));
 pub struct YtDlp {
     pub(crate) binary_path: PathBuf,
     pub(crate) cookies_path: Option<PathBuf>,
+    pub(crate) runner: Arc<dyn CommandRunner>,
+    pub(crate) max_retries: u32,
+    pub(crate) retry_delay: Duration,
 }
 
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
 impl YtDlp {
     /// Creates a new `YtDlp` instance using the vendored binary.
     ///
@@ -46,6 +53,9 @@ impl YtDlp {
         Ok(YtDlp {
             binary_path: Self::resolve_yt_dlp_binary()?,
             cookies_path,
+            runner: Arc::new(SystemCommandRunner),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
         })
     }
 
@@ -121,9 +131,50 @@ impl YtDlp {
         YtDlp {
             binary_path: binary_path.into(),
             cookies_path: cookies_path.map(Into::into),
+            runner: Arc::new(SystemCommandRunner),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
         }
     }
 
+    /// Replaces the [`CommandRunner`] used to spawn yt-dlp, ffmpeg and ffprobe.
+    ///
+    /// Mostly useful in tests, see [`crate::FakeCommandRunner`] (requires the `test-utils` feature).
+    pub fn with_command_runner(mut self, runner: impl CommandRunner + 'static) -> Self {
+        self.runner = Arc::new(runner);
+        self
+    }
+
+    /// Sets how many times a transient yt-dlp failure is attempted in total,
+    /// and how long to wait between attempts. Defaults to 3 attempts, 2 seconds apart.
+    pub fn with_retries(mut self, max_retries: u32, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries.max(1);
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Returns the version reported by the yt-dlp binary in use, e.g. `"2025.03.31"`.
+    ///
+    /// This may differ from [`YTDLP_RELEASE`] when a custom binary is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the binary cannot be executed or exits with a non-zero status.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn version(&self) -> Result<String, YtDlpError> {
+        let output = self.run_command(&self.binary_path, &["--version"])?;
+
+        if !output.success() {
+            return Err(YtDlpError::NonZeroExit {
+                command: self.binary_path.to_string_lossy().into(),
+                status: output.status.unwrap_or(-1),
+                output: output.stderr,
+            });
+        }
+
+        Ok(output.stdout.trim().to_string())
+    }
+
     /// Downloads a single video from the given URL.
     ///
     /// # Arguments
@@ -186,6 +237,121 @@ impl YtDlp {
         ])
     }
 
+    /// Downloads only the given sections of a video's audio, e.g. a 15 minute region of a
+    /// multi-hour sitting, and converts them to the specified format.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the video whose audio to download.
+    /// * `format` - The desired audio format (`"mp3"`, `"wav"`, `"flac"`, or `"aac"`).
+    /// * `sections` - The time ranges to download.
+    /// * `output_template` - A template string for the output filenames. When downloading more
+    ///   than one section it must contain a section field such as `%(section_start)s`,
+    ///   otherwise every section would be written to the same file.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the produced files, in the order yt-dlp finished them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the download fails or if the output template is invalid.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn download_audio_sections<P: AsRef<Path> + Debug>(
+        &self,
+        url: &str,
+        format: &str,
+        sections: &[TimeRange],
+        output_template: P,
+    ) -> Result<Vec<PathBuf>, YtDlpError> {
+        self.download_sections(
+            url,
+            &["-f", "bestaudio", "-x", "--audio-format", format],
+            sections,
+            output_template.as_ref(),
+        )
+    }
+
+    /// Downloads only the given sections of a video, e.g. to clip a single debate.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the video to download.
+    /// * `format` - The desired video format, e.g `"bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]"`
+    /// * `sections` - The time ranges to download.
+    /// * `output_template` - A template string for the output filenames, see
+    ///   [`YtDlp::download_audio_sections`].
+    ///
+    /// # Returns
+    ///
+    /// The paths of the produced files, in the order yt-dlp finished them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `YtDlpError` if the download fails or if the output template is invalid.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn download_video_sections<P: AsRef<Path> + Debug>(
+        &self,
+        url: &str,
+        format: &str,
+        sections: &[TimeRange],
+        output_template: P,
+    ) -> Result<Vec<PathBuf>, YtDlpError> {
+        self.download_sections(url, &["-f", format], sections, output_template.as_ref())
+    }
+
+    fn download_sections(
+        &self,
+        url: &str,
+        format_args: &[&str],
+        sections: &[TimeRange],
+        output_template: &Path,
+    ) -> Result<Vec<PathBuf>, YtDlpError> {
+        let output_str = output_template
+            .to_str()
+            .ok_or_else(|| YtDlpError::InvalidPath(output_template.display().to_string()))?;
+
+        if sections.is_empty() {
+            return Err(YtDlpError::InvalidTimeRange(
+                "at least one section is required".to_string(),
+            ));
+        }
+        if sections.len() > 1 && !output_str.contains("%(section_") {
+            return Err(YtDlpError::InvalidPath(format!(
+                "Output template must contain a section field (e.g. %(section_start)s) when downloading multiple sections: {output_str}"
+            )));
+        }
+
+        let section_args = sections
+            .iter()
+            .map(|section| section.to_section_arg())
+            .collect::<Vec<_>>();
+
+        let mut args = format_args.to_vec();
+        for section in &section_args {
+            args.extend(["--download-sections", section]);
+        }
+        args.extend([
+            // cut exactly at the requested timestamps rather than the nearest keyframes
+            "--force-keyframes-at-cuts",
+            "--print",
+            "after_move:filepath",
+            "--no-simulate",
+            "--output",
+            output_str,
+            url,
+        ]);
+
+        let stdout = self.run_yt_dlp_with_output(&args)?;
+
+        Ok(stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect())
+    }
+
     /// Downloads all videos from a playlist URL.
     ///
     /// # Arguments
@@ -340,10 +506,18 @@ impl YtDlp {
     /// Runs the `yt-dlp` command with optional `--cookies` support.
     ///
     /// This method appends the cookies argument to the command if `cookies_path` is set.
+    /// Failures are classified from yt-dlp's output and only transient ones
+    /// (see [`YtDlpError::is_transient`]) are retried.
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub(crate) fn run_yt_dlp(&self, args: &[&str]) -> Result<(), YtDlpError> {
-        let max_retries = 3;
-        let retry_delay = std::time::Duration::from_secs(2);
+        self.run_yt_dlp_with_output(args).map(|_| ())
+    }
+
+    /// Same as [`YtDlp::run_yt_dlp`], returning yt-dlp's stdout, e.g. values requested via `--print`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub(crate) fn run_yt_dlp_with_output(&self, args: &[&str]) -> Result<String, YtDlpError> {
+        let max_retries = self.max_retries;
+        let retry_delay = self.retry_delay;
         let mut attempts = 0;
 
         loop {
@@ -351,8 +525,10 @@ impl YtDlp {
             let result = self.run_yt_dlp_once(args);
 
             match result {
-                Ok(()) => return Ok(()),
-                Err(err) if matches!(err, YtDlpError::NonZeroExit { .. }) => {
+                Ok(stdout) => return Ok(stdout),
+                // only transient failures are worth retrying, anything else (expired cookies,
+                // private videos, geo blocks...) will fail the same way on the next attempt
+                Err(err) if err.is_transient() => {
                     tracing::warn!(
                         ?err,
                         attempts,
@@ -372,8 +548,8 @@ impl YtDlp {
         }
     }
 
-    fn run_yt_dlp_once(&self, args: &[&str]) -> Result<(), YtDlpError> {
-        let mut cmd = std::process::Command::new(&self.binary_path);
+    fn run_yt_dlp_once(&self, args: &[&str]) -> Result<String, YtDlpError> {
+        let mut cmd_args = Vec::with_capacity(args.len() + 2);
 
         if let Some(ref cookies) = self.cookies_path {
             if !cookies.exists() {
@@ -382,52 +558,99 @@ impl YtDlp {
                     cookies.display()
                 )));
             }
-            cmd.arg("--cookies").arg(cookies);
+            let cookies = cookies
+                .to_str()
+                .ok_or_else(|| YtDlpError::InvalidPath(cookies.display().to_string()))?;
+            cmd_args.extend(["--cookies", cookies]);
         }
 
-        cmd.args(args);
-        let output = cmd.output()?;
+        cmd_args.extend_from_slice(args);
+        let output = self.run_command(&self.binary_path, &cmd_args)?;
 
-        if output.status.success() {
-            Ok(())
+        if output.success() {
+            Ok(output.stdout)
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-
-            let output_msg = if !stderr.trim().is_empty() {
-                stderr.into()
-            } else if !stdout.trim().is_empty() {
-                stdout.into()
+            let output_msg = if !output.stderr.trim().is_empty() {
+                output.stderr
+            } else if !output.stdout.trim().is_empty() {
+                output.stdout
             } else {
                 "yt-dlp exited with non-zero status but produced no output.".into()
             };
 
-            Err(YtDlpError::NonZeroExit {
-                command: self.binary_path.to_string_lossy().into(),
-                status: output.status.code().unwrap_or(-1),
-                output: output_msg,
-            })
+            Err(YtDlpError::from_yt_dlp_output(
+                self.binary_path.to_string_lossy().into(),
+                output.status.unwrap_or(-1),
+                output_msg,
+            ))
         }
     }
 
+    /// Runs `program` through the configured [`CommandRunner`], mapping a missing
+    /// program to [`YtDlpError::BinaryNotFound`].
+    fn run_command(&self, program: &Path, args: &[&str]) -> Result<CommandOutput, YtDlpError> {
+        self.runner.run(program, args).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                YtDlpError::BinaryNotFound(program.display().to_string())
+            }
+            _ => YtDlpError::ExecutionError(e),
+        })
+    }
+
     #[cfg(any(feature = "audio-processing", feature = "video-processing"))]
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub(crate) fn run_ffmpeg(&self, args: &[&str]) -> Result<(), YtDlpError> {
-        if which::which("ffmpeg").is_err() {
-            return Err(YtDlpError::BinaryNotFound("ffmpeg".to_string()));
-        }
-        let output = Command::new("ffmpeg").args(args).output()?;
+        self.run_ffmpeg_with_output(args).map(|_| ())
+    }
 
-        if output.status.success() {
-            Ok(())
+    /// Runs `ffmpeg`, returning its stderr where ffmpeg logs filter output such as
+    /// `ebur128` summaries or `silencedetect` events.
+    #[cfg(any(feature = "audio-processing", feature = "video-processing"))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub(crate) fn run_ffmpeg_with_output(&self, args: &[&str]) -> Result<String, YtDlpError> {
+        let output = self.run_command(Path::new("ffmpeg"), args)?;
+
+        if output.success() {
+            Ok(output.stderr)
         } else {
             Err(YtDlpError::NonZeroExit {
                 command: "ffmpeg".to_string(),
-                status: output.status.code().unwrap_or(-1),
-                output: String::from_utf8_lossy(&output.stdout.to_vec()).into(),
+                status: output.status.unwrap_or(-1),
+                output: output.stderr,
             })
         }
     }
+
+    /// Runs `ffprobe`, returning its stdout.
+    #[cfg(any(feature = "audio-processing", feature = "video-processing"))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub(crate) fn run_ffprobe(&self, args: &[&str]) -> Result<String, YtDlpError> {
+        let output = self.run_command(Path::new("ffprobe"), args)?;
+
+        if output.success() {
+            Ok(output.stdout)
+        } else {
+            Err(YtDlpError::NonZeroExit {
+                command: "ffprobe".to_string(),
+                status: output.status.unwrap_or(-1),
+                output: output.stderr,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+impl YtDlp {
+    /// A `YtDlp` that runs every command through `runner` and retries without waiting.
+    pub(crate) fn fake(runner: &crate::runner::FakeCommandRunner) -> Self {
+        YtDlp {
+            binary_path: PathBuf::from("yt-dlp"),
+            cookies_path: None,
+            runner: Arc::new(runner.clone()),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: Duration::ZERO,
+        }
+    }
 }
 
 #[cfg(all(test, feature = "yt-dlp-vendored"))]
@@ -449,6 +672,7 @@ impl Drop for YtDlp {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::{FakeCommandRunner, RecordedCommand, ScriptedResponse};
     use glob::glob;
     use std::env;
     use std::fs;
@@ -464,6 +688,13 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[cfg(feature = "yt-dlp-vendored")]
+    #[test]
+    fn test_version_matches_vendored_release() {
+        let ytdlp = YtDlp::new().unwrap();
+        assert_eq!(ytdlp.version().unwrap(), YTDLP_RELEASE);
+    }
+
     #[cfg(not(feature = "yt-dlp-vendored"))]
     #[test]
     #[ignore = "This test depends on an existing installation of yt-dlp"]
@@ -579,7 +810,12 @@ mod tests {
 
     #[test]
     fn test_download_invalid_url_fails() {
-        let ytdlp = YtDlp::new().unwrap();
+        let unsupported = ScriptedResponse::failure(1)
+            .stderr("ERROR: [generic] Unsupported URL: https://www.youtube.com/watch?v=invalid");
+        let runner = (0..DEFAULT_MAX_RETRIES).fold(FakeCommandRunner::new(), |runner, _| {
+            runner.respond(unsupported.clone())
+        });
+        let ytdlp = YtDlp::fake(&runner);
         let output_path = std::env::temp_dir().join("invalid.%(ext)s");
         let result = ytdlp.download_video(
             "https://www.youtube.com/watch?v=invalid",
@@ -593,4 +829,180 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_download_audio_builds_expected_args() {
+        let runner = FakeCommandRunner::new();
+        let ytdlp = YtDlp::fake(&runner);
+
+        ytdlp
+            .download_audio(TEST_VIDEO_URL, "mp3", "/tmp/audio/%(id)s.%(ext)s")
+            .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![RecordedCommand {
+                program: PathBuf::from("yt-dlp"),
+                args: [
+                    "-f",
+                    "bestaudio",
+                    "-x",
+                    "--audio-format",
+                    "mp3",
+                    "--output",
+                    "/tmp/audio/%(id)s.%(ext)s",
+                    TEST_VIDEO_URL,
+                ]
+                .map(String::from)
+                .to_vec(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cookies_are_passed_before_other_args() {
+        let temp_dir = tempdir().unwrap();
+        let cookies_path = temp_dir.path().join("cookies.txt");
+        fs::write(&cookies_path, "# Netscape HTTP Cookie File").unwrap();
+
+        let runner = FakeCommandRunner::new();
+        let mut ytdlp = YtDlp::fake(&runner);
+        ytdlp.cookies_path = Some(cookies_path.clone());
+
+        ytdlp
+            .download_auto_sub(TEST_VIDEO_URL, "/tmp/subs/%(id)s")
+            .unwrap();
+
+        let args = &runner.calls()[0].args;
+        assert_eq!(args[0], "--cookies");
+        assert_eq!(args[1], cookies_path.to_str().unwrap());
+        assert_eq!(args.last().unwrap(), TEST_VIDEO_URL);
+    }
+
+    #[test]
+    fn test_transient_failures_are_retried_until_exhausted() {
+        let failure = ScriptedResponse::failure(1).stderr("ERROR: Unable to download webpage");
+        let runner = FakeCommandRunner::new()
+            .respond(failure.clone())
+            .respond(failure.clone())
+            .respond(failure);
+        let ytdlp = YtDlp::fake(&runner);
+
+        let result = ytdlp.download_video(TEST_VIDEO_URL, "best", "/tmp/video.%(ext)s");
+
+        assert!(matches!(
+            result,
+            Err(YtDlpError::NonZeroExit { status: 1, .. })
+        ));
+        assert_eq!(runner.calls().len(), DEFAULT_MAX_RETRIES as usize);
+    }
+
+    #[test]
+    fn test_retry_recovers_from_rate_limit() {
+        let runner = FakeCommandRunner::new()
+            .respond(
+                ScriptedResponse::failure(1).stderr("ERROR: HTTP Error 429: Too Many Requests"),
+            )
+            .respond(ScriptedResponse::success());
+        let ytdlp = YtDlp::fake(&runner);
+
+        let result = ytdlp.download_video(TEST_VIDEO_URL, "best", "/tmp/video.%(ext)s");
+
+        assert!(result.is_ok(), "Expected success, got {result:?}");
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_non_transient_failures_are_not_retried() {
+        let runner = FakeCommandRunner::new().respond(
+            ScriptedResponse::failure(1)
+                .stderr("ERROR: [youtube] dQw4w9WgXcQ: Sign in to confirm you’re not a bot."),
+        );
+        let ytdlp = YtDlp::fake(&runner);
+
+        let result = ytdlp.download_audio(TEST_VIDEO_URL, "mp3", "/tmp/audio.%(ext)s");
+
+        assert!(matches!(result, Err(YtDlpError::SignInRequired(_))));
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    #[test]
+    fn test_download_audio_sections_returns_produced_files() {
+        let temp_dir = tempdir().unwrap();
+        let first = temp_dir.path().join("clip_600.mp3");
+        let second = temp_dir.path().join("clip_3600.mp3");
+
+        let runner = FakeCommandRunner::new().respond(
+            ScriptedResponse::success()
+                .stdout(format!("{}\n{}\n", first.display(), second.display()))
+                .creates_file(&first, b"ID3".to_vec())
+                .creates_file(&second, b"ID3".to_vec()),
+        );
+        let ytdlp = YtDlp::fake(&runner);
+        let sections = [
+            "00:10:00-00:25:00".parse::<TimeRange>().unwrap(),
+            "01:00:00-01:05:00".parse::<TimeRange>().unwrap(),
+        ];
+
+        let files = ytdlp
+            .download_audio_sections(
+                TEST_VIDEO_URL,
+                "mp3",
+                &sections,
+                temp_dir.path().join("clip_%(section_start)s.%(ext)s"),
+            )
+            .unwrap();
+
+        assert_eq!(files, vec![first.clone(), second.clone()]);
+        assert!(first.exists() && second.exists());
+
+        let call = &runner.calls()[0];
+        assert!(call.has_arg_pair("--download-sections", "*00:10:00.000-00:25:00.000"));
+        assert!(call.has_arg_pair("--download-sections", "*01:00:00.000-01:05:00.000"));
+        assert!(call.has_arg_pair("--print", "after_move:filepath"));
+        assert!(call.has_arg_pair("--audio-format", "mp3"));
+    }
+
+    #[test]
+    fn test_multiple_sections_require_section_output_template() {
+        let runner = FakeCommandRunner::new();
+        let ytdlp = YtDlp::fake(&runner);
+        let sections = [
+            "00:10:00-00:25:00".parse::<TimeRange>().unwrap(),
+            "01:00:00-01:05:00".parse::<TimeRange>().unwrap(),
+        ];
+
+        let result =
+            ytdlp.download_audio_sections(TEST_VIDEO_URL, "mp3", &sections, "/tmp/clip.%(ext)s");
+
+        assert!(matches!(result, Err(YtDlpError::InvalidPath(_))));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[test]
+    fn test_version_reads_stdout() {
+        let runner =
+            FakeCommandRunner::new().respond(ScriptedResponse::success().stdout("2025.03.31\n"));
+        let ytdlp = YtDlp::fake(&runner);
+
+        assert_eq!(ytdlp.version().unwrap(), "2025.03.31");
+        assert_eq!(runner.calls()[0].args, ["--version"]);
+    }
+
+    #[cfg(feature = "audio-processing")]
+    #[test]
+    fn test_missing_ffmpeg_is_reported() {
+        use crate::{AudioProcessingConfig, AudioProcessor};
+
+        let runner = FakeCommandRunner::new().respond(ScriptedResponse::not_found());
+        let ytdlp = YtDlp::fake(&runner);
+
+        let result = ytdlp.normalize_volume(
+            "/tmp/in.mp3",
+            "/tmp/out.mp3",
+            &AudioProcessingConfig::default(),
+        );
+
+        assert!(matches!(result, Err(YtDlpError::BinaryNotFound(bin)) if bin == "ffmpeg"));
+    }
 }